@@ -1,10 +1,16 @@
-use std::fmt::{Display, Write};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Write},
+    hash::Hash,
+};
 
-use crate::vec2d::Vec2D;
+use crate::vec2d::{Vec2D, DOWN, LEFT, RIGHT, UP};
 
 use self::iterators::{EdgeIterator, GridIterator, GridLineIterator};
+use self::view::{GridView, Transform};
 
 pub mod iterators;
+pub mod view;
 
 pub struct GridContentIterator<'a, T> {
     grid: &'a Grid<T>,
@@ -34,6 +40,8 @@ pub struct GridContentMutIterator<'a, T> {
 //     }
 // }
 
+/// A 2D grid stored row-major, the cell at (x,y) lives at index `y * width + x`
+/// Unless stated otherwise, iterating a grid goes row by row, left to right: (0,0), (1,0), .. (0,1), ..
 pub struct Grid<T> {
     bytes: Vec<T>,
     width: usize,
@@ -98,21 +106,35 @@ pub enum Direction {
     Right,
 }
 
-impl<T> Grid<T> {
+/// A position on a grid, (0,0) being the top left cell
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct GridPos(pub Vec2D<usize>);
+
+/// A position in the world a grid is placed in, see `Grid::to_grid` and `Grid::to_world`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct WorldPos(pub Vec2D<i32>);
+
+impl<T: Default + Clone> Grid<T> {
+    /// A grid of `width * height` default cells
     pub fn new(width: usize, height: usize) -> Self {
-        let mut content = Vec::new();
-        content.reserve(width * height);
         Self {
-            bytes: content,
+            bytes: vec![T::default(); width * height],
             width,
             height,
         }
     }
+}
 
+impl<T> Grid<T> {
+    /// Wraps row-major content, the height follows from the content length
     pub fn new_with_content(content: Vec<T>, width: usize) -> Result<Self, String> {
         let len = content.len();
-        if len % width != 0 {
-            Err("Content length is not a multiple of width".to_string())
+        if width == 0 {
+            Err(format!("Can't make a grid of width 0 from {len} cells"))
+        } else if len % width != 0 {
+            Err(format!(
+                "Content length {len} is not a multiple of width {width}"
+            ))
         } else {
             Ok(Self {
                 bytes: content,
@@ -122,6 +144,17 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Like `Grid::from_str`, but every char is turned into a cell by f
+    pub fn from_str_with(str: &str, f: impl Fn(char) -> T) -> Self {
+        let width = checked_line_length(str, |line| line.chars().count());
+
+        Self {
+            bytes: str.lines().flat_map(str::chars).map(f).collect(),
+            width,
+            height: str.lines().count(),
+        }
+    }
+
     // Get a character at the given coordinates
     pub fn get(&self, x: usize, y: usize) -> Option<&T> {
         self.bytes.get(x + y * self.width)
@@ -133,17 +166,56 @@ impl<T> Grid<T> {
 
     pub fn get_by_vec(&self, pos: &Vec2D<i32>) -> Option<&T> {
         // println!("{} {}", pos.x, pos.y);
-        self.bytes.get(pos.x as usize + pos.y as usize * self.width)
+        self.bytes.get(self.index_of_position(pos)?)
     }
 
-    pub fn get_mut_by_vec(&mut self, pos: Vec2D<usize>) -> Option<&mut T> {
-        self.bytes.get_mut(pos.x + pos.y * self.width)
+    pub fn get_mut_by_vec(&mut self, pos: &Vec2D<i32>) -> Option<&mut T> {
+        let index = self.index_of_position(pos)?;
+        self.bytes.get_mut(index)
     }
 
     pub fn set(&mut self, pos: &Vec2D<i32>, i: T) {
-        let index = self.index_of_position(pos);
+        *self
+            .get_mut_by_vec(pos)
+            .expect("Position should be on the grid") = i;
+    }
+
+    pub fn get_by_pos(&self, pos: &GridPos) -> Option<&T> {
+        if pos.0.x >= self.width {
+            return None;
+        }
+        self.get(pos.0.x, pos.0.y)
+    }
+
+    pub fn set_by_pos(&mut self, pos: &GridPos, i: T) {
+        assert!(pos.0.x < self.width, "Position should be on the grid");
 
-        *self.bytes.get_mut(index).unwrap() = i;
+        *self
+            .get_mut(pos.0.x, pos.0.y)
+            .expect("Position should be on the grid") = i;
+    }
+
+    /// Translates a world position onto the grid, origin being the world position of the top left cell
+    /// Returns None if the position falls outside the grid
+    pub fn to_grid(&self, origin: &WorldPos, pos: &WorldPos) -> Option<GridPos> {
+        let local = pos.0 - origin.0;
+        self.index_of_position(&local)?;
+
+        Some(GridPos(Vec2D {
+            x: local.x as usize,
+            y: local.y as usize,
+        }))
+    }
+
+    /// Translates a grid position into the world, origin being the world position of the top left cell
+    pub fn to_world(&self, origin: &WorldPos, pos: &GridPos) -> WorldPos {
+        WorldPos(
+            origin.0
+                + Vec2D {
+                    x: pos.0.x as i32,
+                    y: pos.0.y as i32,
+                },
+        )
     }
 
     // pub fn size(&self) -> usize {
@@ -168,8 +240,32 @@ impl<T> Grid<T> {
         })
     }
 
-    pub fn index_of_position(&self, position: &Vec2D<i32>) -> usize {
-        position.x as usize + position.y as usize * self.width
+    /// Returns the index of the position, or None if it falls outside the grid
+    pub fn index_of_position(&self, position: &Vec2D<i32>) -> Option<usize> {
+        let x = usize::try_from(position.x).ok()?;
+        let y = usize::try_from(position.y).ok()?;
+
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(x + y * self.width)
+    }
+
+    /// Whether the position lies on the outer border of the grid
+    pub fn is_edge(&self, pos: Vec2D<i32>) -> bool {
+        self.index_of_position(&pos).is_some()
+            && (pos.x == 0
+                || pos.y == 0
+                || pos.x as usize == self.width - 1
+                || pos.y as usize == self.height - 1)
+    }
+
+    /// Whether the position is one of the four corner cells
+    pub fn is_corner(&self, pos: Vec2D<i32>) -> bool {
+        self.index_of_position(&pos).is_some()
+            && (pos.x == 0 || pos.x as usize == self.width - 1)
+            && (pos.y == 0 || pos.y as usize == self.height - 1)
     }
 
     pub fn iter(&self) -> GridContentIterator<T> {
@@ -252,6 +348,124 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Steps needed to reach every cell from start, moving orthogonally where passable(from, to) allows
+    /// Unreachable cells are None
+    pub fn bfs_distances(
+        &self,
+        start: Vec2D<i32>,
+        passable: impl Fn(&T, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        self.bfs_from_sources(&[start], passable)
+    }
+
+    /// Like bfs_distances, but starting from every source at once, each cell gets the distance to its nearest source
+    /// Sources outside the grid are ignored
+    pub fn bfs_from_sources(
+        &self,
+        sources: &[Vec2D<i32>],
+        passable: impl Fn(&T, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        let mut distances = Grid {
+            bytes: vec![None; self.bytes.len()],
+            width: self.width,
+            height: self.height,
+        };
+
+        let mut frontier = VecDeque::new();
+        for source in sources {
+            if distances.get_by_vec(source).is_some_and(Option::is_none) {
+                distances.set(source, Some(0));
+                frontier.push_back((*source, 0));
+            }
+        }
+
+        let mut neighbours = Vec::with_capacity(4);
+
+        while let Some((pos, distance)) = frontier.pop_front() {
+            let from = self.get_by_vec(&pos).expect("Frontier to stay on the grid");
+
+            neighbours.clear();
+            self.get_neighbours(pos, &mut neighbours);
+
+            for neighbour in &neighbours {
+                let to = self
+                    .get_by_vec(neighbour)
+                    .expect("Neighbours to be on the grid");
+                if distances.get_by_vec(neighbour).is_some_and(Option::is_none)
+                    && passable(from, to)
+                {
+                    distances.set(neighbour, Some(distance + 1));
+                    frontier.push_back((*neighbour, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Counts the neighbours of pos for which pred holds, including the 4 diagonal neighbours if diagonal is set
+    pub fn count_neighbours_matching(
+        &self,
+        pos: Vec2D<i32>,
+        diagonal: bool,
+        pred: impl Fn(&T) -> bool,
+    ) -> usize {
+        let mut neighbours = Vec::with_capacity(8);
+        if diagonal {
+            self.get_neighbours_diagonal(pos, &mut neighbours);
+        } else {
+            self.get_neighbours(pos, &mut neighbours);
+        }
+
+        neighbours
+            .iter()
+            .filter_map(|n| self.get_by_vec(n))
+            .filter(|cell| pred(cell))
+            .count()
+    }
+
+    /// Counts the orthogonally connected regions, neighbouring cells belong to the same region if same holds for them
+    pub fn count_regions(&self, same: impl Fn(&T, &T) -> bool) -> usize {
+        let mut visited = vec![false; self.bytes.len()];
+        let mut stack = vec![];
+        let mut neighbours = Vec::with_capacity(4);
+        let mut regions = 0;
+
+        for start in 0..self.bytes.len() {
+            if visited[start] {
+                continue;
+            }
+
+            // Flood fill the region start belongs to
+            regions += 1;
+            visited[start] = true;
+            stack.push(start);
+
+            while let Some(index) = stack.pop() {
+                let pos = self
+                    .position_of_index(index)
+                    .expect("Index to be on the grid");
+
+                neighbours.clear();
+                self.get_neighbours(pos, &mut neighbours);
+
+                for neighbour in &neighbours {
+                    let neighbour_index = self
+                        .index_of_position(neighbour)
+                        .expect("Neighbours to be on the grid");
+                    if !visited[neighbour_index]
+                        && same(&self.bytes[index], &self.bytes[neighbour_index])
+                    {
+                        visited[neighbour_index] = true;
+                        stack.push(neighbour_index);
+                    }
+                }
+            }
+        }
+
+        regions
+    }
+
     fn increment_for_direction(&self, dir: Direction) -> i32 {
         match dir {
             Direction::Up => -(self.width as i32),
@@ -265,6 +479,19 @@ impl<T> Grid<T> {
         EdgeIterator::new(self)
     }
 
+    /// Yields the cells strictly beyond from in the given direction, up to the edge of the grid
+    pub fn ray(&self, from: Vec2D<i32>, dir: Direction) -> impl Iterator<Item = (Vec2D<i32>, &T)> {
+        let step = match dir {
+            Direction::Up => UP,
+            Direction::Down => DOWN,
+            Direction::Left => LEFT,
+            Direction::Right => RIGHT,
+        };
+
+        std::iter::successors(Some(from + step), move |pos| Some(*pos + step))
+            .map_while(|pos| self.get_by_vec(&pos).map(|cell| (pos, cell)))
+    }
+
     pub fn line_iter(&self, start: Vec2D<usize>, dir: Direction) -> GridLineIterator<T> {
         let iterations_left = match dir {
             Direction::Up => start.y + 1,
@@ -293,34 +520,185 @@ impl<T> Grid<T> {
         grid_iterator.zip(self.bytes.iter())
     }
 
+    /// Yields every cell on the outer border once, corners included, in row-major order
+    /// Unlike `edges`, corners aren't visited twice and the grid doesn't need to be square
+    pub fn border_cells(&self) -> impl Iterator<Item = (Vec2D<i32>, &T)> {
+        self.iter_with_pos()
+            .map(|(pos, cell)| {
+                (
+                    Vec2D {
+                        x: pos.x as i32,
+                        y: pos.y as i32,
+                    },
+                    cell,
+                )
+            })
+            .filter(|(pos, _)| self.is_edge(*pos))
+    }
+
+    /// Lazily yields the position of every cell matching the predicate, in row-major order
+    pub fn positions_where<'a>(
+        &'a self,
+        pred: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Vec2D<i32>> + 'a {
+        self.iter_with_pos()
+            .filter(move |(_, cell)| pred(cell))
+            .map(|(pos, _)| Vec2D {
+                x: pos.x as i32,
+                y: pos.y as i32,
+            })
+    }
+
+    /// Iterates column by column, top to bottom: (0,0), (0,1), .. (1,0), ..
+    pub fn iter_column_major(&self) -> impl Iterator<Item = (Vec2D<usize>, &T)> {
+        (0..self.width).flat_map(move |x| {
+            (0..self.height).map(move |y| (Vec2D { x, y }, &self.bytes[y * self.width + x]))
+        })
+    }
+
     pub fn iter_mut_with_pos(&mut self) -> impl Iterator<Item = (Vec2D<usize>, &mut T)> {
         let grid_iterator = GridIterator::new(self.width, self.height);
         grid_iterator.zip(self.bytes.iter_mut())
         // self.bytes.iter_mut().zip(GridIterator)
     }
 
+    /// A view reading this grid through the given coordinate transform, without copying it
+    pub fn transform_coords(&self, transform: Transform) -> GridView<'_, T> {
+        GridView::new(self, transform)
+    }
+
+    /// Applies f to every cell, returning a grid of the results
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            bytes: self.bytes.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Like `map`, but f also receives the position of the cell
+    pub fn map_with_pos<U>(&self, f: impl Fn(Vec2D<i32>, &T) -> U) -> Grid<U> {
+        Grid {
+            bytes: self
+                .iter_with_pos()
+                .map(|(pos, cell)| {
+                    f(
+                        Vec2D {
+                            x: pos.x as i32,
+                            y: pos.y as i32,
+                        },
+                        cell,
+                    )
+                })
+                .collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
     pub fn take(self) -> Vec<T> {
         self.bytes
     }
 }
 
+impl<T: Hash + Eq> Grid<T> {
+    /// How many cells hold each distinct value
+    pub fn value_counts(&self) -> HashMap<&T, usize> {
+        let mut counts = HashMap::new();
+        for cell in self.iter() {
+            *counts.entry(cell).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Copies a window of the grid into a new grid
+    pub fn subgrid(
+        &self,
+        top_left: Vec2D<usize>,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, String> {
+        if top_left.x + width > self.width || top_left.y + height > self.height {
+            return Err(format!(
+                "Subgrid of {width}x{height} at {},{} doesn't fit in {}x{} grid",
+                top_left.x, top_left.y, self.width, self.height
+            ));
+        }
+
+        let bytes = self
+            .bytes
+            .chunks(self.width)
+            .skip(top_left.y)
+            .take(height)
+            .flat_map(|row| row[top_left.x..top_left.x + width].iter().cloned())
+            .collect();
+
+        Ok(Self {
+            bytes,
+            width,
+            height,
+        })
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<T: Sync> Grid<T> {
+    /// Parallel version of `iter_with_pos`, cells are processed in any order but collect back in row-major order
+    pub fn par_iter_with_pos(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = (Vec2D<usize>, &T)> {
+        use rayon::prelude::*;
+
+        let width = self.width;
+        self.bytes.par_iter().enumerate().map(move |(index, cell)| {
+            (
+                Vec2D {
+                    x: index % width,
+                    y: index / width,
+                },
+                cell,
+            )
+        })
+    }
+
+    /// Applies f to every cell in parallel, returning a grid of the results
+    pub fn par_map<U: Send>(&self, f: impl Fn(&T) -> U + Sync + Send) -> Grid<U> {
+        use rayon::prelude::*;
+
+        Grid {
+            bytes: self.bytes.par_iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+// Length of the first line, panics listing every line length if they aren't all equal
+fn checked_line_length(str: &str, line_length: impl Fn(&str) -> usize) -> usize {
+    let size = str.lines().next().map_or(0, &line_length);
+
+    let equal_sizes = str.lines().all(|line| line_length(line) == size);
+    if !equal_sizes {
+        println!("The following line lenghts were seen");
+        str.lines()
+            .map(&line_length)
+            .for_each(|line_len| println!("{line_len}"));
+
+        panic!("Line lenghts don't match");
+    }
+
+    size
+}
+
 impl Grid<u8> {
     pub fn from_str(str: &str) -> Self {
         //1: Ensure all lines have the same length
-        let size = str.lines().next().unwrap().bytes().len();
+        let size = checked_line_length(str, |line| line.bytes().len());
         let mut v: Vec<u8> = Vec::new();
         v.reserve(size * size);
 
-        let equal_sizes = str.lines().all(|line| line.bytes().len() == size);
-        if !equal_sizes {
-            println!("The following line lenghts were seen");
-            str.lines()
-                .map(|line| line.bytes().len())
-                .for_each(|line_len| println!("{line_len}"));
-
-            panic!("Line lenghts don't match");
-        }
-
         str.lines()
             .map(|line| line.bytes())
             .for_each(|f| v.extend(f));
@@ -331,6 +709,49 @@ impl Grid<u8> {
             height: str.lines().count(),
         }
     }
+
+    /// Like `from_str`, but pads lines shorter than the longest one with fill instead of panicking
+    pub fn from_str_padded(str: &str, fill: u8) -> Self {
+        let width = str.lines().map(str::len).max().unwrap_or(0);
+        let mut v: Vec<u8> = Vec::with_capacity(width * str.lines().count());
+
+        for line in str.lines() {
+            v.extend(line.bytes());
+            v.resize(v.len() + width - line.len(), fill);
+        }
+
+        Self {
+            bytes: v,
+            width,
+            height: str.lines().count(),
+        }
+    }
+
+    /// Converts a grid of ASCII digits into their numeric values
+    pub fn to_digits(&self) -> Self {
+        let bytes = self
+            .bytes
+            .iter()
+            .map(|b| {
+                (*b as char)
+                    .to_digit(10)
+                    .expect("Grid should only contain digits") as u8
+            })
+            .collect();
+
+        Self {
+            bytes,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Like `Grid::from_str`, but with a cell per char rather than per byte
+    pub fn from_char_grid(str: &str) -> Self {
+        Self::from_str_with(str, |c| c)
+    }
 }
 
 impl Display for Grid<char> {
@@ -464,6 +885,98 @@ mod tests {
         vec.len()
     }
 
+    #[test]
+    fn get_by_vec() {
+        let mut grid = Grid::from_str("ab\ncd");
+
+        assert_eq!(grid.get_by_vec(&Vec2D { x: 1, y: 0 }), Some(&b'b'));
+        assert_eq!(grid.get_mut_by_vec(&Vec2D { x: 1, y: 0 }), Some(&mut b'b'));
+
+        *grid.get_mut_by_vec(&Vec2D { x: 0, y: 1 }).unwrap() = b'x';
+        assert_eq!(grid.get_by_vec(&Vec2D { x: 0, y: 1 }), Some(&b'x'));
+    }
+
+    #[test]
+    fn get_by_vec_out_of_bounds() {
+        let mut grid = Grid::from_str("ab\ncd");
+
+        // Would wrap onto the next row without a bounds check
+        assert_eq!(grid.get_by_vec(&Vec2D { x: 2, y: 0 }), None);
+        assert_eq!(grid.get_mut_by_vec(&Vec2D { x: 2, y: 0 }), None);
+
+        assert_eq!(grid.get_by_vec(&Vec2D { x: -1, y: 1 }), None);
+        assert_eq!(grid.get_mut_by_vec(&Vec2D { x: -1, y: 1 }), None);
+
+        assert_eq!(grid.get_by_vec(&Vec2D { x: 0, y: 2 }), None);
+        assert_eq!(grid.index_of_position(&Vec2D { x: 1, y: 1 }), Some(3));
+    }
+
+    #[test]
+    fn to_digits() {
+        let grid = Grid::from_str("09\n45").to_digits();
+
+        assert_eq!(grid.get(1, 0), Some(&9));
+        assert_eq!(grid.take(), vec![0, 9, 4, 5]);
+    }
+
+    #[test]
+    fn subgrid() {
+        let grid = Grid::from_str("abcd\nefgh\nijkl\nmnop");
+        let sub = grid.subgrid(Vec2D { x: 1, y: 2 }, 2, 2).unwrap();
+
+        assert_eq!(sub.width(), 2);
+        assert_eq!(sub.height(), 2);
+        assert_eq!(sub.to_string(), "jk\nno\n");
+    }
+
+    #[test]
+    fn subgrid_out_of_bounds() {
+        let grid = Grid::from_str("abcd\nefgh\nijkl\nmnop");
+
+        assert!(grid.subgrid(Vec2D { x: 3, y: 0 }, 2, 2).is_err());
+        assert!(grid.subgrid(Vec2D { x: 0, y: 3 }, 2, 2).is_err());
+        assert!(grid.subgrid(Vec2D { x: 0, y: 0 }, 4, 4).is_ok());
+    }
+
+    #[test]
+    fn world_grid_conversion() {
+        let grid = Grid::from_str("abc\ndef");
+        let origin = WorldPos(Vec2D { x: 494, y: -2 });
+
+        let grid_pos = grid.to_grid(&origin, &WorldPos(Vec2D { x: 496, y: -1 }));
+        assert_eq!(grid_pos, Some(GridPos(Vec2D { x: 2, y: 1 })));
+        assert_eq!(grid.get_by_pos(&grid_pos.unwrap()), Some(&b'f'));
+
+        assert_eq!(
+            grid.to_world(&origin, &GridPos(Vec2D { x: 2, y: 1 })),
+            WorldPos(Vec2D { x: 496, y: -1 })
+        );
+        assert_eq!(
+            grid.to_world(&origin, &GridPos(Vec2D { x: 0, y: 0 })),
+            origin
+        );
+    }
+
+    #[test]
+    fn world_grid_conversion_out_of_bounds() {
+        let grid = Grid::from_str("abc\ndef");
+        let origin = WorldPos(Vec2D { x: 494, y: -2 });
+
+        assert_eq!(
+            grid.to_grid(&origin, &WorldPos(Vec2D { x: 493, y: -2 })),
+            None
+        );
+        assert_eq!(
+            grid.to_grid(&origin, &WorldPos(Vec2D { x: 497, y: -2 })),
+            None
+        );
+        assert_eq!(
+            grid.to_grid(&origin, &WorldPos(Vec2D { x: 494, y: 0 })),
+            None
+        );
+        assert_eq!(grid.get_by_pos(&GridPos(Vec2D { x: 3, y: 0 })), None);
+    }
+
     #[test]
     fn neigbours() {
         //1x1, should see nothing
@@ -484,4 +997,369 @@ mod tests {
         assert_eq!(run_nb_test(3, Vec2D { x: 1, y: 2 }), 5);
         assert_eq!(run_nb_test(3, Vec2D { x: 0, y: 1 }), 5);
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_map() {
+        use rayon::prelude::*;
+
+        let grid = Grid::from_str("30373\n25512\n65332\n33549\n35390").to_digits();
+        let double = |n: &u8| u32::from(*n) * 2;
+
+        let parallel = grid.par_map(double);
+        let sequential = Grid::new_with_content(grid.iter().map(double).collect(), 5).unwrap();
+        assert_eq!(parallel.take(), sequential.take());
+
+        let mut positions: Vec<(Vec2D<usize>, u8)> =
+            grid.par_iter_with_pos().map(|(pos, n)| (pos, *n)).collect();
+        positions.sort_by_key(|(pos, _)| (pos.y, pos.x));
+        let expected: Vec<(Vec2D<usize>, u8)> =
+            grid.iter_with_pos().map(|(pos, n)| (pos, *n)).collect();
+        assert_eq!(positions, expected);
+    }
+
+    #[test]
+    fn from_str_padded() {
+        let grid = Grid::from_str_padded("ab\nabcd\n\nabc", b'.');
+
+        assert_eq!(grid.width(), 4);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid.to_string(), "ab..\nabcd\n....\nabc.\n");
+
+        let grid = Grid::from_str_padded("abc\ndef", b'.');
+        assert_eq!(grid.take(), Grid::from_str("abc\ndef").take());
+    }
+
+    #[test]
+    fn ray() {
+        let grid = Grid::from_str("abcd\nefgh\nijkl");
+        let from = Vec2D { x: 1, y: 1 };
+        let ray = |dir| -> String { grid.ray(from, dir).map(|(_, c)| *c as char).collect() };
+
+        assert_eq!(ray(Direction::Up), "b");
+        assert_eq!(ray(Direction::Down), "j");
+        assert_eq!(ray(Direction::Left), "e");
+        assert_eq!(ray(Direction::Right), "gh");
+
+        let positions: Vec<Vec2D<i32>> = grid
+            .ray(from, Direction::Right)
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(positions, vec![Vec2D { x: 2, y: 1 }, Vec2D { x: 3, y: 1 }]);
+
+        assert_eq!(grid.ray(Vec2D { x: 3, y: 0 }, Direction::Right).count(), 0);
+    }
+
+    #[test]
+    fn bfs_distances() {
+        let grid = Grid::from_str("..#.\n..#.\n....\n##.#\n.#..");
+        let distances = grid.bfs_distances(Vec2D { x: 0, y: 0 }, |_, to| *to != b'#');
+
+        let at = |x, y| *distances.get_by_vec(&Vec2D { x, y }).unwrap();
+        assert_eq!(at(0, 0), Some(0));
+        assert_eq!(at(1, 1), Some(2));
+        assert_eq!(at(3, 0), Some(7)); // Around the wall
+        assert_eq!(at(3, 4), Some(7));
+        assert_eq!(at(2, 0), None); // Wall
+        assert_eq!(at(0, 4), None); // Walled off
+
+        // Only allow stepping up at most one, like day 12
+        let hills = Grid::from_str("abz\nbcd");
+        let distances = hills.bfs_distances(Vec2D { x: 0, y: 0 }, |from, to| *to <= from + 1);
+        assert_eq!(*distances.get_by_vec(&Vec2D { x: 2, y: 0 }).unwrap(), None);
+        assert_eq!(
+            *distances.get_by_vec(&Vec2D { x: 2, y: 1 }).unwrap(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn count_regions() {
+        let grid = Grid::from_str("#####\n#..##\n#####\n##..#\n#####");
+
+        // The surrounding wall plus two pockets
+        assert_eq!(grid.count_regions(|a, b| a == b), 3);
+        assert_eq!(grid.count_regions(|_, _| true), 1);
+
+        let grid = Grid::from_str(".#.\n#.#");
+        assert_eq!(grid.count_regions(|a, b| a == b), 6);
+    }
+
+    #[test]
+    fn from_char_grid() {
+        let grid = Grid::from_char_grid("a→c\n└─┘");
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(1, 0), Some(&'→'));
+        assert_eq!(grid.get(2, 1), Some(&'┘'));
+        assert_eq!(grid.to_string(), "a→c\n└─┘\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Line lenghts don't match")]
+    fn from_char_grid_uneven() {
+        Grid::from_char_grid("ab\nc");
+    }
+
+    #[test]
+    fn value_counts() {
+        let grid = Grid::from_str("30373\n25512").to_digits();
+        let counts = grid.value_counts();
+
+        assert_eq!(counts.len(), 6);
+        assert_eq!(counts[&3], 3);
+        assert_eq!(counts[&5], 2);
+        assert_eq!(counts[&2], 2);
+        assert_eq!(counts[&0], 1);
+        assert_eq!(counts[&7], 1);
+        assert_eq!(counts[&1], 1);
+        assert_eq!(counts.get(&9), None);
+    }
+
+    #[test]
+    fn bfs_from_sources() {
+        let grid = Grid::from_str("....\n.##.\n....");
+        let sources = [
+            Vec2D { x: 0, y: 0 },
+            Vec2D { x: 3, y: 2 },
+            Vec2D { x: 9, y: 9 }, // Off the grid
+        ];
+        let distances = grid.bfs_from_sources(&sources, |_, to| *to != b'#');
+
+        let at = |x, y| *distances.get_by_vec(&Vec2D { x, y }).unwrap();
+        assert_eq!(at(0, 0), Some(0));
+        assert_eq!(at(3, 2), Some(0));
+        assert_eq!(at(3, 0), Some(2)); // Closer to the second source
+        assert_eq!(at(0, 2), Some(2));
+        assert_eq!(at(2, 0), Some(2));
+        assert_eq!(at(1, 1), None); // Wall
+
+        // Without sources nothing is reachable
+        let distances = grid.bfs_from_sources(&[], |_, _| true);
+        assert!(distances.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn from_str_with() {
+        let grid = Grid::from_str_with("303\n255", |c| c as u8 - b'0');
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(&3));
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(
+            grid.iter().copied().collect::<Vec<_>>(),
+            vec![3, 0, 3, 2, 5, 5]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Line lenghts don't match")]
+    fn from_str_with_uneven() {
+        Grid::from_str_with("ab\nc", |c| c);
+    }
+
+    #[test]
+    fn iteration_order() {
+        let grid = Grid::from_str("abc\ndef");
+
+        let row_major: Vec<(usize, usize, u8)> = grid
+            .iter_with_pos()
+            .map(|(pos, c)| (pos.x, pos.y, *c))
+            .collect();
+        assert_eq!(
+            row_major,
+            vec![
+                (0, 0, b'a'),
+                (1, 0, b'b'),
+                (2, 0, b'c'),
+                (0, 1, b'd'),
+                (1, 1, b'e'),
+                (2, 1, b'f')
+            ]
+        );
+
+        let column_major: Vec<(usize, usize, u8)> = grid
+            .iter_column_major()
+            .map(|(pos, c)| (pos.x, pos.y, *c))
+            .collect();
+        assert_eq!(
+            column_major,
+            vec![
+                (0, 0, b'a'),
+                (0, 1, b'd'),
+                (1, 0, b'b'),
+                (1, 1, b'e'),
+                (2, 0, b'c'),
+                (2, 1, b'f')
+            ]
+        );
+    }
+
+    #[test]
+    fn count_neighbours_matching() {
+        let grid = Grid::from_str("#.#\n.##\n#..");
+        let is_wall = |c: &u8| *c == b'#';
+
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 1, y: 1 }, true, is_wall),
+            4
+        );
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 1, y: 1 }, false, is_wall),
+            1
+        );
+
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 0, y: 0 }, true, is_wall),
+            1
+        );
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 0, y: 0 }, false, is_wall),
+            0
+        );
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 2, y: 2 }, true, is_wall),
+            2
+        );
+        assert_eq!(
+            grid.count_neighbours_matching(Vec2D { x: 2, y: 2 }, false, is_wall),
+            1
+        );
+    }
+
+    #[test]
+    fn map_with_pos() {
+        let grid = Grid::new_with_content(vec![0u8; 16], 4).unwrap();
+
+        let perimeter =
+            grid.map_with_pos(|pos, _| pos.x == 0 || pos.y == 0 || pos.x == 3 || pos.y == 3);
+
+        assert_eq!(perimeter.width(), 4);
+        assert_eq!(perimeter.height(), 4);
+        assert_eq!(perimeter.iter().filter(|b| **b).count(), 12);
+        assert_eq!(perimeter.get(0, 2), Some(&true));
+        assert_eq!(perimeter.get(3, 1), Some(&true));
+        assert_eq!(perimeter.get(1, 1), Some(&false));
+        assert_eq!(perimeter.get(2, 2), Some(&false));
+
+        let doubled = Grid::from_str("12\n34").to_digits().map(|n| n * 2);
+        assert_eq!(doubled.take(), vec![2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn edges_and_corners() {
+        let grid = Grid::new_with_content(vec![0u8; 16], 4).unwrap();
+
+        let edges = grid.map_with_pos(|pos, _| grid.is_edge(pos));
+        assert_eq!(edges.iter().filter(|b| **b).count(), 12);
+        assert!(grid.is_edge(Vec2D { x: 0, y: 2 }));
+        assert!(grid.is_edge(Vec2D { x: 3, y: 1 }));
+        assert!(grid.is_edge(Vec2D { x: 2, y: 3 }));
+        assert!(!grid.is_edge(Vec2D { x: 1, y: 1 }));
+        assert!(!grid.is_edge(Vec2D { x: 2, y: 2 }));
+        assert!(!grid.is_edge(Vec2D { x: -1, y: 0 }));
+        assert!(!grid.is_edge(Vec2D { x: 4, y: 0 }));
+
+        let corners = grid.map_with_pos(|pos, _| grid.is_corner(pos));
+        assert_eq!(corners.iter().filter(|b| **b).count(), 4);
+        assert!(grid.is_corner(Vec2D { x: 0, y: 0 }));
+        assert!(grid.is_corner(Vec2D { x: 3, y: 0 }));
+        assert!(grid.is_corner(Vec2D { x: 0, y: 3 }));
+        assert!(grid.is_corner(Vec2D { x: 3, y: 3 }));
+        assert!(!grid.is_corner(Vec2D { x: 0, y: 1 }));
+        assert!(!grid.is_corner(Vec2D { x: 4, y: 4 }));
+    }
+
+    #[test]
+    fn positions_where() {
+        let grid = Grid::from_str("#..\n.#.\n..#\n#..");
+
+        let walls: Vec<Vec2D<i32>> = grid.positions_where(|b| *b == b'#').collect();
+
+        assert_eq!(
+            walls,
+            vec![
+                Vec2D { x: 0, y: 0 },
+                Vec2D { x: 1, y: 1 },
+                Vec2D { x: 2, y: 2 },
+                Vec2D { x: 0, y: 3 },
+            ]
+        );
+        assert_eq!(grid.positions_where(|b| *b == b'@').next(), None);
+    }
+
+    #[test]
+    fn transform_coords() {
+        use super::view::Transform;
+
+        // abc
+        // def
+        let grid = Grid::from_str("abc\ndef");
+
+        let identity = grid.transform_coords(Transform::Identity);
+        assert_eq!(identity.get(1, 0), Some(&b'b'));
+
+        let reflect_x = grid.transform_coords(Transform::ReflectX);
+        assert_eq!(reflect_x.get(0, 0), Some(&b'c'));
+        assert_eq!(reflect_x.get(2, 1), Some(&b'd'));
+        assert_eq!(reflect_x.get(3, 0), None);
+
+        let reflect_y = grid.transform_coords(Transform::ReflectY);
+        assert_eq!(reflect_y.get(0, 0), Some(&b'd'));
+        assert_eq!(reflect_y.get(2, 1), Some(&b'c'));
+
+        let transposed = grid.transform_coords(Transform::Transpose);
+        assert_eq!((transposed.width(), transposed.height()), (2, 3));
+        assert_eq!(transposed.get(1, 0), Some(&b'd'));
+        assert_eq!(transposed.get(0, 2), Some(&b'c'));
+        assert_eq!(transposed.get(2, 0), None);
+    }
+
+    #[test]
+    fn new_fills_with_default() {
+        let grid: Grid<u8> = Grid::new(3, 2);
+        assert_eq!(grid.width, 3);
+        assert_eq!(grid.height, 2);
+        assert_eq!(grid.take(), vec![0; 6]);
+    }
+
+    #[test]
+    fn new_with_content() {
+        let grid = Grid::new_with_content(vec![1, 2, 3, 4, 5, 6], 2).unwrap();
+        assert_eq!(grid.height, 3);
+
+        assert_eq!(
+            Grid::new_with_content(vec![1, 2, 3], 2).err().unwrap(),
+            "Content length 3 is not a multiple of width 2"
+        );
+        assert!(Grid::new_with_content(vec![1, 2, 3], 0).is_err());
+    }
+
+    #[test]
+    fn iter_with_pos_shares_the_grid() {
+        let grid = Grid::new_with_content(vec![1, 2, 3, 4], 2).unwrap();
+        let first = grid.get(0, 0);
+
+        let cells: Vec<_> = grid
+            .iter_with_pos()
+            .map(|(pos, cell)| (pos.x, pos.y, *cell))
+            .collect();
+
+        assert_eq!(cells, vec![(0, 0, 1), (1, 0, 2), (0, 1, 3), (1, 1, 4)]);
+        assert_eq!(first, Some(&1));
+    }
+
+    #[test]
+    fn border_cells() {
+        let grid = Grid::new_with_content(vec![1, 2, 1, 2, 9, 2, 1, 2, 1], 3).unwrap();
+
+        let border: Vec<_> = grid.border_cells().collect();
+        assert_eq!(border.len(), 8);
+        assert!(border.iter().all(|(_, cell)| **cell != 9));
+        assert_eq!(border.iter().filter(|(_, cell)| **cell == 1).count(), 4);
+        assert_eq!(border[0].0, Vec2D { x: 0, y: 0 });
+        assert_eq!(border[7].0, Vec2D { x: 2, y: 2 });
+    }
 }
@@ -94,7 +94,8 @@ impl<'a, T> Iterator for GridLineIterator<'a, T> {
     }
 }
 
-// Iterates over a grid, row by row
+/// Yields every position of a grid in row-major order, matching how `Grid` stores its cells
+/// (0,0), (1,0), .. (width-1,0), (0,1), ..
 pub struct GridIterator {
     pos: Vec2D<usize>,
     max: Vec2D<usize>,
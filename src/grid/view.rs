@@ -0,0 +1,52 @@
+use super::Grid;
+
+/// How a `GridView` remaps coordinates before reading the underlying grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    Identity,
+    ReflectX,  // Mirror left to right
+    ReflectY,  // Mirror top to bottom
+    Transpose, // Swap x and y
+}
+
+/// A read-only view of a grid with remapped coordinates, nothing is copied
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    transform: Transform,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub(crate) fn new(grid: &'a Grid<T>, transform: Transform) -> Self {
+        Self { grid, transform }
+    }
+
+    pub fn width(&self) -> usize {
+        match self.transform {
+            Transform::Transpose => self.grid.height,
+            _ => self.grid.width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self.transform {
+            Transform::Transpose => self.grid.width,
+            _ => self.grid.height,
+        }
+    }
+
+    /// Get the cell at the given coordinates of the view
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let (x, y) = match self.transform {
+            Transform::Identity => (x, y),
+            Transform::ReflectX => (self.grid.width - 1 - x, y),
+            Transform::ReflectY => (x, self.grid.height - 1 - y),
+            Transform::Transpose => (y, x),
+        };
+
+        self.grid.get(x, y)
+    }
+}
@@ -1,3 +1,5 @@
+use std::{process::ExitCode, time::Duration};
+
 #[allow(dead_code)]
 mod grid;
 mod parsing;
@@ -7,9 +9,85 @@ mod range;
 mod rangeset;
 mod solutions;
 #[allow(dead_code)]
+mod util;
+#[allow(dead_code)]
 mod vec2d;
 
-fn main() {
+fn main() -> ExitCode {
     println!("Advent of Code 2022");
-    solutions::run();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let verbose = args.iter().any(|arg| arg == "--verbose");
+
+    let timeout = match args.iter().position(|arg| arg == "--timeout") {
+        Some(index) => {
+            let Some(seconds) = args.get(index + 1).and_then(|n| n.parse::<u64>().ok()) else {
+                println!("--timeout expects a number of seconds");
+                return ExitCode::FAILURE;
+            };
+            Some(Duration::from_secs(seconds))
+        }
+        None => None,
+    };
+    if let Some(timeout) = timeout {
+        solutions::set_timeout(timeout);
+    }
+
+    if args.iter().any(|arg| arg == "--check") {
+        return if solutions::check(timeout) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+
+    if let Some(index) = args.iter().position(|arg| arg == "--repeat") {
+        let Some(repeats) = args.get(index + 1).and_then(|n| n.parse::<usize>().ok()) else {
+            println!("--repeat expects a number of repeats");
+            return ExitCode::FAILURE;
+        };
+
+        solutions::run_repeated(repeats);
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(index) = args.iter().position(|arg| arg == "--input") {
+        let Some(path) = args.get(index + 1) else {
+            println!("--input expects a path");
+            return ExitCode::FAILURE;
+        };
+        let Some(day_number) = args
+            .iter()
+            .position(|arg| arg == "--day")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|n| n.parse::<i32>().ok())
+        else {
+            println!("--input expects a day number given with --day");
+            return ExitCode::FAILURE;
+        };
+
+        if !solutions::run_from_path(day_number, path, timeout, verbose) {
+            println!("No solution for day {day_number}");
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if args.iter().any(|arg| arg == "--diff") {
+        solutions::run_diff(timeout);
+        return ExitCode::SUCCESS;
+    }
+
+    if args.iter().any(|arg| arg == "--example-inline") {
+        solutions::run_examples(timeout, verbose);
+        return ExitCode::SUCCESS;
+    }
+
+    if args.iter().any(|arg| arg == "--time-only") {
+        solutions::run_time_only(timeout);
+    } else {
+        solutions::run(timeout, verbose);
+    }
+    ExitCode::SUCCESS
 }
@@ -3,7 +3,8 @@ fn is_number_char(char: &char) -> bool {
     char.is_ascii_digit() || char == &'-'
 }
 
-pub fn consume_number_from_char_iter<T>(iter: &mut T) -> i32
+/// Parses the next number in the iterator, None when it holds no further number
+pub fn consume_number_from_char_iter<T>(iter: &mut T) -> Option<i32>
 where
     T: Iterator<Item = char>,
 {
@@ -12,7 +13,7 @@ where
         .take_while(is_number_char)
         .collect();
 
-    chars.parse().expect("Chars to parse into numbers")
+    chars.parse().ok()
 }
 
 pub fn consume_when<T, P, I>(iter: &mut T, predicate: &P) -> Vec<I>
@@ -25,6 +26,37 @@ where
         .collect()
 }
 
+/// Strips trailing whitespace from every line and drops trailing blank lines, every line ends in a newline
+pub fn normalize_input(input: &str) -> String {
+    let mut lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Splits input into blocks separated by one or more blank lines, each block without its final line ending
+/// Handles both `\n` and `\r\n` line endings
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut lines = input.split_inclusive('\n').peekable();
+    let mut offset = 0;
+
+    std::iter::from_fn(move || {
+        // Skip the blank lines in front of the block
+        while let Some(line) = lines.next_if(|line| line.trim().is_empty()) {
+            offset += line.len();
+        }
+
+        let start = offset;
+        while let Some(line) = lines.next_if(|line| !line.trim().is_empty()) {
+            offset += line.len();
+        }
+
+        (offset > start).then(|| input[start..offset].trim_end_matches(['\r', '\n']))
+    })
+}
+
 // pub fn chunk_by<T, P, I, R>(iter: &mut T, mut predicate: P) -> TakeWhile<SkipWhile<T, P>, P>
 // where
 //     T: Iterator<Item = I>,
@@ -34,3 +66,35 @@ where
 
 //     i2.take_while(predicate_2)
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, normalize_input};
+
+    #[test]
+    fn normalize_trailing_whitespace() {
+        assert_eq!(
+            normalize_input("A Y  \r\nB X\t\n\nC Z \n\n"),
+            "A Y\nB X\n\nC Z\n"
+        );
+        assert_eq!(normalize_input("1,2\n3,4"), "1,2\n3,4\n");
+        assert_eq!(normalize_input("  indented \n"), "  indented\n");
+        assert_eq!(normalize_input("\n \n"), "");
+    }
+
+    #[test]
+    fn blank_line_blocks() {
+        let input = "1000\r\n2000\r\n\r\n3000\n\n\n4000\n5000\r\n\r\n\n";
+
+        assert_eq!(
+            blocks(input).collect::<Vec<_>>(),
+            vec!["1000\r\n2000", "3000", "4000\n5000"]
+        );
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("\n\r\n").count(), 0);
+        assert_eq!(
+            blocks("    [D]\n 1 \n\nmove").collect::<Vec<_>>(),
+            vec!["    [D]\n 1 ", "move"]
+        );
+    }
+}
@@ -63,11 +63,12 @@ impl Ranging for (i32, i32) {
         self.0 < other.0 && self.1 > other.1
     }
 
+    // The part both ranges cover, only meaningful when they overlap
     fn overlap(&self, other: &Self) -> (i32, i32) {
-        if self.overlaps(other) {
+        if self.contains_inclusive(other) {
             return *other;
         }
-        if other.overlaps(self) {
+        if other.contains_inclusive(self) {
             return *self;
         }
 
@@ -124,4 +125,12 @@ mod tests {
 
         assert_eq!(range.remove(&cut), vec![(17, 20)]);
     }
+
+    #[test]
+    fn overlap() {
+        assert_eq!((2, 6).overlap(&(4, 8)), (4, 6));
+        assert_eq!((4, 8).overlap(&(2, 6)), (4, 6));
+        assert_eq!((2, 8).overlap(&(3, 7)), (3, 7));
+        assert_eq!((3, 7).overlap(&(2, 8)), (3, 7));
+    }
 }
@@ -13,11 +13,35 @@
 //
 //
 //
+use std::fmt::{Debug, Display};
+
 use crate::range::Ranging;
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Clone)]
 pub struct RangeSet(pub Vec<i32>);
 
+// Formats as [(a,b), (c,d)]
+impl Display for RangeSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[")?;
+        for (i, (start, end)) in self.iter_ranges().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "({start},{end})")?;
+        }
+        f.write_str("]")
+    }
+}
+
+// Lists the ranges rather than the flat vector of bounds
+impl Debug for RangeSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RangeSet")?;
+        f.debug_list().entries(self.iter_ranges()).finish()
+    }
+}
+
 // #[derive(PartialEq, Eq)]
 // enum RangeSlot {
 //     Start,
@@ -139,8 +163,17 @@ impl RangeSet {
 
     // This has grown into an insane tree of edge cases that should be faster then the wastfully slow fallback option
     // I'd love to simplify this somewhat but oh dear
-    #[allow(clippy::nonminimal_bool)]
     pub fn insert(&mut self, new_range: (i32, i32)) {
+        self.insert_unchecked(new_range);
+
+        #[cfg(test)]
+        if let Err(e) = self.validate() {
+            panic!("Invalid set after inserting {new_range:?}: {e}");
+        }
+    }
+
+    #[allow(clippy::nonminimal_bool)]
+    fn insert_unchecked(&mut self, new_range: (i32, i32)) {
         let len = self.0.len();
 
         let left_index = self.position_report(new_range.0);
@@ -377,6 +410,15 @@ impl RangeSet {
     }
 
     pub fn remove(&mut self, cut: (i32, i32)) {
+        self.remove_unchecked(cut);
+
+        #[cfg(test)]
+        if let Err(e) = self.validate() {
+            panic!("Invalid set after removing {cut:?}: {e}");
+        }
+    }
+
+    fn remove_unchecked(&mut self, cut: (i32, i32)) {
         let len = self.0.len();
         let left_index = self.position_report(cut.0);
         let right_index = self.position_report(cut.1);
@@ -454,6 +496,51 @@ impl RangeSet {
 
         new_to_insert.into_iter().for_each(|r| self.insert(r));
     }
+
+    /// Removes a single point, splitting a range in two if the point is inside it
+    pub fn remove_point(&mut self, n: i32) {
+        // Ranges exclude their end, so i32::MAX is never in the set and there is nothing to remove
+        if let Some(end) = n.checked_add(1) {
+            self.remove((n, end));
+        }
+    }
+
+    /// Translates every range by delta, shifting all endpoints keeps them sorted
+    pub fn shift(&mut self, delta: i32) {
+        self.0.iter_mut().for_each(|n| *n += delta);
+    }
+
+    /// Checks the bounds are an even amount of strictly increasing numbers
+    /// That makes every range non-empty, and keeps ranges sorted without overlapping or touching each other
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.0.len().is_multiple_of(2) {
+            return Err(format!("Odd number of bounds {:?}", self.0));
+        }
+
+        match self.0.windows(2).position(|pair| pair[0] >= pair[1]) {
+            Some(i) if i.is_multiple_of(2) => Err(format!(
+                "Range ({},{}) at index {i} is empty or reversed",
+                self.0[i],
+                self.0[i + 1]
+            )),
+            Some(i) => Err(format!(
+                "Range ending at {} overlaps or touches the range starting at {} (index {i})",
+                self.0[i],
+                self.0[i + 1]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts how many ranges share at least one point with the given range
+    pub fn touching_range_count(&self, range: (i32, i32)) -> usize {
+        // Every range before this index ends at or before the start of the given range
+        let first_range = self.0.partition_point(|n| *n <= range.0) / 2;
+        // Every range before this index starts before the end of the given range
+        let last_range = self.0.partition_point(|n| *n < range.1).div_ceil(2);
+
+        last_range.saturating_sub(first_range)
+    }
 }
 
 #[cfg(test)]
@@ -628,6 +715,66 @@ mod tests {
         assert_eq!(overlap, vec![(0, 6, 8)]);
     }
 
+    #[test]
+    fn remove_point_interior() {
+        let mut range = RangeSet::default();
+        range.insert((10, 20));
+
+        range.remove_point(12);
+
+        let ranges: Vec<(i32, i32)> = range.iter_ranges().collect();
+        assert_eq!(ranges, vec![(10, 12), (13, 20)]);
+    }
+
+    #[test]
+    fn remove_point_endpoints() {
+        let mut range = RangeSet::default();
+        range.insert((10, 20));
+
+        range.remove_point(10);
+        assert_eq!(range.iter_ranges().collect::<Vec<_>>(), vec![(11, 20)]);
+
+        range.remove_point(19);
+        assert_eq!(range.iter_ranges().collect::<Vec<_>>(), vec![(11, 19)]);
+
+        range.remove_point(30);
+        assert_eq!(range.iter_ranges().collect::<Vec<_>>(), vec![(11, 19)]);
+    }
+
+    #[test]
+    fn remove_point_at_max() {
+        let mut range = RangeSet::default();
+        range.insert((i32::MAX - 5, i32::MAX));
+
+        range.remove_point(i32::MAX);
+        assert_eq!(
+            range.iter_ranges().collect::<Vec<_>>(),
+            vec![(i32::MAX - 5, i32::MAX)]
+        );
+
+        range.remove_point(i32::MAX - 1);
+        assert_eq!(
+            range.iter_ranges().collect::<Vec<_>>(),
+            vec![(i32::MAX - 5, i32::MAX - 1)]
+        );
+    }
+
+    #[test]
+    fn touching_range_count() {
+        let mut range = RangeSet::default();
+        range.insert((0, 5));
+        range.insert((10, 15));
+        range.insert((20, 25));
+
+        assert_eq!(range.touching_range_count((-5, 0)), 0);
+        assert_eq!(range.touching_range_count((-5, 1)), 1);
+        assert_eq!(range.touching_range_count((5, 10)), 0);
+        assert_eq!(range.touching_range_count((4, 11)), 2);
+        assert_eq!(range.touching_range_count((12, 13)), 1);
+        assert_eq!(range.touching_range_count((-100, 100)), 3);
+        assert_eq!(range.touching_range_count((25, 30)), 0);
+    }
+
     #[test]
     fn edge_cases() {
         {
@@ -657,4 +804,69 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn formatting() {
+        let mut range = RangeSet::default();
+        assert_eq!(range.to_string(), "[]");
+
+        range.insert((5, 10));
+        range.insert((15, 20));
+        range.insert((-3, 0));
+
+        assert_eq!(range.to_string(), "[(-3,0), (5,10), (15,20)]");
+        assert_eq!(format!("{range:?}"), "RangeSet[(-3, 0), (5, 10), (15, 20)]");
+    }
+
+    #[test]
+    fn shift() {
+        let mut range = RangeSet::default();
+        range.insert((5, 10));
+        range.insert((15, 20));
+
+        range.shift(-5);
+
+        assert_eq!(
+            range.iter_ranges().collect::<Vec<_>>(),
+            vec![(0, 5), (10, 15)]
+        );
+        assert!(range.is_in_range(0));
+        assert!(!range.is_in_range(5));
+        assert!(range.is_in_range(14));
+        assert!(!range.is_in_range(15));
+    }
+
+    #[test]
+    fn validate() {
+        let mut range = RangeSet::default();
+        assert_eq!(range.validate(), Ok(()));
+
+        range.insert((5, 10));
+        range.insert((15, 20));
+        range.remove((7, 8));
+        assert_eq!(range.validate(), Ok(()));
+
+        assert_eq!(
+            RangeSet(vec![5, 10, 15]).validate(),
+            Err("Odd number of bounds [5, 10, 15]".to_owned())
+        );
+        assert_eq!(
+            RangeSet(vec![5, 10, 12, 12]).validate(),
+            Err("Range (12,12) at index 2 is empty or reversed".to_owned())
+        );
+        assert_eq!(
+            RangeSet(vec![5, 10, 10, 12]).validate(),
+            Err(
+                "Range ending at 10 overlaps or touches the range starting at 10 (index 1)"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            RangeSet(vec![5, 10, 8, 12]).validate(),
+            Err(
+                "Range ending at 10 overlaps or touches the range starting at 8 (index 1)"
+                    .to_owned()
+            )
+        );
+    }
 }
@@ -1,4 +1,16 @@
-use std::{fmt::Display, fs, io::Read, str::FromStr, time};
+use std::{
+    fmt::Display,
+    fs,
+    io::Read,
+    str::FromStr,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        OnceLock,
+    },
+    thread, time,
+};
+
+use crate::parsing::normalize_input;
 
 mod day1;
 mod day10;
@@ -27,6 +39,19 @@ pub enum PartResult {
 
 static MISSING_OUTPUT_MESSAGE: &str = "<MISSING>";
 
+static SEARCH_BUDGET: OnceLock<time::Duration> = OnceLock::new();
+
+/// Lets slow searches settle for their best answer so far instead of running past the timeout
+pub fn set_timeout(timeout: time::Duration) {
+    let _ = SEARCH_BUDGET.set(timeout * 9 / 10);
+}
+
+/// How long a search may take before returning its best answer so far, None without a timeout
+/// Kept a tenth below the timeout, so the answer arrives before the runner stops waiting for it
+fn search_budget() -> Option<time::Duration> {
+    SEARCH_BUDGET.get().copied()
+}
+
 impl FromStr for PartResult {
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         Ok(value
@@ -42,20 +67,55 @@ impl From<i32> for PartResult {
         Self::Int(val)
     }
 }
+
+impl PartResult {
+    /// The numeric value of the answer, strings are parsed, None if it isn't a number or doesn't fit
+    #[cfg(test)]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(n) => Some(i64::from(*n)),
+            Self::UInt(n) => i64::try_from(*n).ok(),
+            Self::Str(s) => s.parse().ok(),
+        }
+    }
+}
 #[derive(Debug)]
 pub struct DayOutput {
-    part1: Option<PartResult>,
-    part2: Option<PartResult>,
+    parts: Vec<Option<PartResult>>,
+}
+
+impl DayOutput {
+    pub fn new(parts: Vec<Option<PartResult>>) -> Self {
+        Self { parts }
+    }
+
+    /// The common case of a day with a part 1 and part 2 answer
+    pub fn two(part1: Option<PartResult>, part2: Option<PartResult>) -> Self {
+        Self::new(vec![part1, part2])
+    }
+
+    /// Get the answer for the given part, counting from 1
+    pub fn part(&self, part_number: usize) -> Option<&PartResult> {
+        self.parts.get(part_number.checked_sub(1)?)?.as_ref()
+    }
+
+    pub fn part_count(&self) -> usize {
+        self.parts.len()
+    }
 }
 
 impl TryFrom<&str> for DayOutput {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let (left, right) = value.split_once(',').ok_or("Error splitting string")?;
+        if !value.contains(',') {
+            return Err("Error splitting string");
+        }
 
-        Ok(Self {
-            part1: Some(PartResult::Str(left.to_owned())),
-            part2: Some(PartResult::Str(right.to_owned())),
-        })
+        Ok(Self::new(
+            value
+                .split(',')
+                .map(|part| Some(PartResult::Str(part.to_owned())))
+                .collect(),
+        ))
     }
 
     type Error = &'static str;
@@ -65,6 +125,8 @@ pub struct SolutionOutput {
     values: DayOutput,
     duration: time::Duration,
     day_number: i32,
+    input_bytes: usize,
+    input_lines: usize,
 }
 
 pub struct NoInputFileErr {
@@ -98,40 +160,244 @@ enum DayError {
 
 type DayFn = fn(&str) -> Result<DayOutput, LogicError>;
 
-fn run_day(n: i32, solution: DayFn) -> Result<SolutionOutput, DayError> {
-    let r = get_input(n).map_err(|er| DayError::NoInputFileErr(er.path))?;
+// Days whose parsers depend on the exact input layout, day 5 reads crates from fixed columns
+const RAW_INPUT_DAYS: [i32; 1] = [5];
 
+fn prepare_input(day_number: i32, input: String) -> String {
+    if RAW_INPUT_DAYS.contains(&day_number) {
+        input
+    } else {
+        normalize_input(&input)
+    }
+}
+
+fn time_solution(solution: DayFn, input: &str) -> (Result<DayOutput, LogicError>, time::Duration) {
     let time_start = time::Instant::now();
-    let output = solution(&r);
-    let duration = time_start.elapsed();
+    let output = solution(input);
+    (output, time_start.elapsed())
+}
+
+/// Runs the solution on a worker thread, giving up once the timeout passes
+/// A thread can't be killed, a timed out worker is leaked and keeps running in the background while the caller moves on
+fn time_solution_with_timeout(
+    solution: DayFn,
+    input: &str,
+    timeout: time::Duration,
+) -> (Result<DayOutput, LogicError>, time::Duration) {
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_owned();
+
+    thread::spawn(move || {
+        // The receiver is gone if we timed out, nobody is left to care about the result
+        let _ = sender.send(time_solution(solution, &input));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => (Err(LogicError("timed out".to_owned())), timeout),
+        Err(RecvTimeoutError::Disconnected) => {
+            (Err(LogicError("panicked".to_owned())), time::Duration::ZERO)
+        }
+    }
+}
+
+fn run_day(
+    n: i32,
+    solution: DayFn,
+    timeout: Option<time::Duration>,
+) -> Result<SolutionOutput, DayError> {
+    let r = get_input(n).map_err(|er| DayError::NoInputFileErr(er.path))?;
+
+    run_input(n, solution, r, timeout)
+}
+
+fn run_input(
+    n: i32,
+    solution: DayFn,
+    input: String,
+    timeout: Option<time::Duration>,
+) -> Result<SolutionOutput, DayError> {
+    let r = prepare_input(n, input);
+
+    let (output, duration) = match timeout {
+        Some(timeout) => time_solution_with_timeout(solution, &r, timeout),
+        None => time_solution(solution, &r),
+    };
 
     output
         .map(|o| SolutionOutput {
             values: o,
             duration,
             day_number: n,
+            input_bytes: r.len(),
+            input_lines: r.lines().count(),
         })
         .map_err(|e| DayError::LogicError(e.0))
 }
 
-pub fn run() {
-    print_result(run_day(1, day1::solve));
-    print_result(run_day(2, day2::solve));
-    print_result(run_day(3, day3::solve));
-    print_result(run_day(4, day4::solve));
-    print_result(run_day(5, day5::solve));
-    print_result(run_day(6, day6::solve));
-    print_result(run_day(7, day7::solve));
-    print_result(run_day(8, day8::solve));
-    print_result(run_day(9, day9::solve));
-    print_result(run_day(10, day10::solve));
-    print_result(run_day(11, day11::solve));
-    print_result(run_day(12, day12::solve));
-    print_result(run_day(13, day13::solve));
-    print_result(run_day(14, day14::solve));
-    print_result(run_day(15, day15::solve));
-    print_result(run_day(16, day16::solve));
-    print_result(run_day(17, day17::solve));
+/// Common interface every day implements
+pub trait Solution {
+    const DAY: u32;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError>;
+
+    /// The example input from the puzzle description
+    fn example() -> &'static str;
+}
+
+struct DayEntry {
+    day_number: i32,
+    solve: DayFn,
+    example: fn() -> &'static str,
+}
+
+const fn entry<S: Solution>() -> DayEntry {
+    DayEntry {
+        day_number: S::DAY as i32,
+        solve: S::solve,
+        example: S::example,
+    }
+}
+
+static SOLUTIONS: [DayEntry; 17] = [
+    entry::<day1::Day1>(),
+    entry::<day2::Day2>(),
+    entry::<day3::Day3>(),
+    entry::<day4::Day4>(),
+    entry::<day5::Day5>(),
+    entry::<day6::Day6>(),
+    entry::<day7::Day7>(),
+    entry::<day8::Day8>(),
+    entry::<day9::Day9>(),
+    entry::<day10::Day10>(),
+    entry::<day11::Day11>(),
+    entry::<day12::Day12>(),
+    entry::<day13::Day13>(),
+    entry::<day14::Day14>(),
+    entry::<day15::Day15>(),
+    entry::<day16::Day16>(),
+    entry::<day17::Day17>(),
+];
+
+fn days() -> impl Iterator<Item = (i32, DayFn)> {
+    SOLUTIONS.iter().map(|e| (e.day_number, e.solve))
+}
+
+/// Every day along with the example input from its puzzle description
+fn days_with_examples() -> impl Iterator<Item = (i32, DayFn, &'static str)> {
+    SOLUTIONS
+        .iter()
+        .map(|e| (e.day_number, e.solve, (e.example)()))
+}
+
+pub fn run(timeout: Option<time::Duration>, verbose: bool) {
+    days().for_each(|(n, solution)| print_result(run_day(n, solution, timeout), verbose));
+}
+
+/// Runs every day on its example input instead of the puzzle input
+pub fn run_examples(timeout: Option<time::Duration>, verbose: bool) {
+    for (n, solution, example) in days_with_examples() {
+        print_result(run_input(n, solution, example.to_owned(), timeout), verbose);
+    }
+}
+
+/// Runs a single day on the input found at path, false if the day doesn't exist
+pub fn run_from_path(
+    day_number: i32,
+    path: &str,
+    timeout: Option<time::Duration>,
+    verbose: bool,
+) -> bool {
+    let Some((n, solution)) = days().find(|(n, _)| *n == day_number) else {
+        return false;
+    };
+
+    let result = read_input_from(path)
+        .map_err(|er| DayError::NoInputFileErr(er.path))
+        .and_then(|input| run_input(n, solution, input, timeout));
+    print_result(result, verbose);
+
+    true
+}
+
+/// Runs every day, printing only the timings followed by the total
+pub fn run_time_only(timeout: Option<time::Duration>) {
+    let mut total = time::Duration::ZERO;
+
+    for (n, solution) in days() {
+        match run_day(n, solution, timeout) {
+            Ok(s) => {
+                total += s.duration;
+                println!("{}", format_timing(&s));
+            }
+            Err(err) => print_result(Err(err), false),
+        }
+    }
+
+    println!("Total:  {:>7}", format_duration(total));
+}
+
+/// Runs every day the given amount of times, printing the fastest and mean duration
+pub fn run_repeated(repeats: usize) {
+    for (n, solution) in days() {
+        let input = match get_input(n) {
+            Ok(input) => prepare_input(n, input),
+            Err(err) => {
+                print_result(Err(DayError::NoInputFileErr(err.path)), false);
+                continue;
+            }
+        };
+
+        let mut durations = Vec::with_capacity(repeats);
+        for _ in 0..repeats {
+            let (output, duration) = time_solution(solution, &input);
+            if let Err(e) = output {
+                print_result(Err(DayError::LogicError(e.0)), false);
+                break;
+            }
+            durations.push(duration);
+        }
+
+        if let Some((min, mean)) = timing_stats(&durations) {
+            println!(
+                "Day {n:2}: min {:>7} mean {:>7}",
+                format_duration(min),
+                format_duration(mean)
+            );
+        }
+    }
+}
+
+/// Fastest and mean duration, None if there are no durations
+fn timing_stats(durations: &[time::Duration]) -> Option<(time::Duration, time::Duration)> {
+    let min = *durations.iter().min()?;
+    let mean = durations.iter().sum::<time::Duration>() / u32::try_from(durations.len()).ok()?;
+
+    Some((min, mean))
+}
+
+/// Runs every day and verifies its output against the solution files, returns false if any day failed
+pub fn check(timeout: Option<time::Duration>) -> bool {
+    let mut all_passed = true;
+
+    for (n, solution) in days() {
+        let result = run_day(n, solution, timeout)
+            .map_err(|err| match err {
+                DayError::NoInputFileErr(s) => format!("No input file {s}"),
+                DayError::LogicError(s) => format!("Error during solve: {s}"),
+            })
+            .and_then(|output| verify(n, output.values));
+
+        match result {
+            Ok(()) => println!("Day {n:2}: OK"),
+            Err(e) => {
+                all_passed = false;
+                println!("Day {n:2}: FAILED {e}");
+            }
+        }
+    }
+
+    all_passed
 }
 
 impl Display for PartResult {
@@ -148,34 +414,57 @@ impl Display for PartResult {
     }
 }
 
+impl DayOutput {
+    fn join_parts(&self, missing: &str) -> String {
+        self.parts
+            .iter()
+            .map(|part| {
+                part.as_ref()
+                    .map_or_else(|| missing.to_owned(), std::string::ToString::to_string)
+            })
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+}
+
 impl Display for DayOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let p1 = self
-            .part1
-            .as_ref()
-            .map_or_else(|| "None".to_owned(), std::string::ToString::to_string);
-
-        let p2 = self
-            .part2
-            .as_ref()
-            .map_or_else(|| "None".to_owned(), std::string::ToString::to_string);
+        write!(f, "{}", self.join_parts("None"))
+    }
+}
 
-        write!(f, "{p1}|{p2}")
+/// Picks the unit so both the fastest and the slowest days read well: 850µs, 12ms, 3.4s
+fn format_duration(d: time::Duration) -> String {
+    if d < time::Duration::from_millis(1) {
+        format!("{}µs", d.as_micros())
+    } else if d < time::Duration::from_secs(1) {
+        format!("{}ms", d.as_millis())
+    } else {
+        format!("{:.1}s", d.as_secs_f64())
     }
 }
 
-fn print_result(r: Result<SolutionOutput, DayError>) {
+fn format_timing(s: &SolutionOutput) -> String {
+    format!("Day {:2}: {:>7}", s.day_number, format_duration(s.duration))
+}
+
+fn format_input_size(s: &SolutionOutput) -> String {
+    format!("({} lines, {} bytes)", s.input_lines, s.input_bytes)
+}
+
+/// Verbose adds the size of the input after the answers
+fn print_result(r: Result<SolutionOutput, DayError>, verbose: bool) {
     match r {
+        Ok(s) if verbose => println!(
+            "{} [{}] {}",
+            format_timing(&s),
+            s.values.join_parts(MISSING_OUTPUT_MESSAGE),
+            format_input_size(&s),
+        ),
         Ok(s) => println!(
-            "Day {:2}: {:5}ms [{}|{}]",
-            s.day_number,
-            s.duration.as_millis(),
-            s.values
-                .part1
-                .unwrap_or_else(|| PartResult::Str(MISSING_OUTPUT_MESSAGE.to_string())),
-            s.values
-                .part2
-                .unwrap_or_else(|| PartResult::Str(MISSING_OUTPUT_MESSAGE.to_string())),
+            "{} [{}]",
+            format_timing(&s),
+            s.values.join_parts(MISSING_OUTPUT_MESSAGE),
         ),
         Err(err) => match err {
             DayError::NoInputFileErr(s) => println!("Error getting file {s}"),
@@ -197,85 +486,395 @@ fn read_file(path: &str) -> Result<String, NoInputFileErr> {
 }
 
 fn get_input(day_number: i32) -> Result<String, NoInputFileErr> {
-    read_file(format!("./data/input/day{day_number}.txt").as_ref())
+    read_input_from(format!("./data/input/day{day_number}.txt").as_ref())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn read_input_from(path: &str) -> Result<String, NoInputFileErr> {
+    read_file(path)
+}
+
+/// Part number of a day's answer, counting from 1
+pub struct Part(usize);
 
-    pub enum Part {
-        Part1,
-        Part2,
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Part {}", self.0)
     }
+}
 
-    impl Display for Part {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            match self {
-                Self::Part1 => write!(f, "Part 1"),
-                Self::Part2 => write!(f, "Part 2"),
+enum VerifyError {
+    Failure(Part, String, String),
+    NoSolution(NoSolutionError),
+    NoResult,
+}
+
+impl From<VerifyError> for String {
+    fn from(value: VerifyError) -> Self {
+        match value {
+            VerifyError::Failure(part, expected, actual) => {
+                format!("{part} Expected {expected} got {actual}")
+            }
+            VerifyError::NoResult => "No result".to_owned(),
+            VerifyError::NoSolution(NoSolutionError::NoFile) => "No solution file".to_owned(),
+            VerifyError::NoSolution(NoSolutionError::ParseFailure) => {
+                "Error parsing solution file".to_owned()
             }
         }
     }
+}
+
+enum NoSolutionError {
+    NoFile,
+    ParseFailure,
+}
+
+fn get_solution(day_number: i32) -> Result<DayOutput, NoSolutionError> {
+    let path = format!("./data/solution/day{day_number}.txt");
+
+    let file = read_file(&path).map_err(|_| NoSolutionError::NoFile)?;
+
+    let doe = DayOutput::try_from(file.lines().next().ok_or(NoSolutionError::ParseFailure)?)
+        .map_err(|_| NoSolutionError::ParseFailure)?;
+
+    Ok(doe)
+}
+
+fn compare_result(
+    expected: Option<&PartResult>,
+    actual: Option<&PartResult>,
+    part: Part,
+) -> Result<(), VerifyError> {
+    let e = expected.ok_or(VerifyError::NoResult)?;
+    let i = actual.ok_or(VerifyError::NoResult)?;
+
+    match e.to_string() == i.to_string() {
+        // Ideally we'd decode types and check those, but this works fine
+        true => Ok(()),
+        false => Err(VerifyError::Failure(part, e.to_string(), i.to_string())),
+    }
+}
+
+fn compare_output(expected: DayOutput, actual: DayOutput) -> Result<(), VerifyError> {
+    let part_count = expected.part_count().max(actual.part_count());
+
+    (1..=part_count).try_for_each(|n| compare_result(expected.part(n), actual.part(n), Part(n)))
+}
+
+/// One line per part, OK when it matches the solution or a red MISMATCH showing both values
+fn diff_lines(expected: &DayOutput, actual: &DayOutput) -> Vec<String> {
+    let part_count = expected.part_count().max(actual.part_count());
+    let show = |part: Option<&PartResult>| {
+        part.map_or_else(|| MISSING_OUTPUT_MESSAGE.to_owned(), ToString::to_string)
+    };
+
+    (1..=part_count)
+        .map(
+            |n| match compare_result(expected.part(n), actual.part(n), Part(n)) {
+                Ok(()) => format!("{}: OK", Part(n)),
+                Err(_) => format!(
+                    "\x1b[31m{}: MISMATCH expected={} actual={}\x1b[0m",
+                    Part(n),
+                    show(expected.part(n)),
+                    show(actual.part(n))
+                ),
+            },
+        )
+        .collect()
+}
+
+/// Runs every day that has a solution file, printing how each part compares to it
+pub fn run_diff(timeout: Option<time::Duration>) {
+    for (n, solution) in days() {
+        let Ok(expected) = get_solution(n) else {
+            continue;
+        };
+
+        match run_day(n, solution, timeout) {
+            Ok(output) => {
+                println!("Day {n:2}:");
+                for line in diff_lines(&expected, &output.values) {
+                    println!("  {line}");
+                }
+            }
+            Err(err) => print_result(Err(err), false),
+        }
+    }
+}
+
+/// Compares the output of a day against its solution file
+pub fn verify(day_number: i32, actual: DayOutput) -> Result<(), String> {
+    let expected = get_solution(day_number).map_err(VerifyError::NoSolution)?;
+
+    Ok(compare_output(expected, actual)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_day(day_number: i32, solution: DayFn) -> Result<(), String> {
+        let input = get_input(day_number)
+            .map_err(|file_error| format!("No input file {}", file_error.path))?;
+        let input = prepare_input(day_number, input);
+        let actual = solution(&input).map_err(|e| e.0)?;
+
+        verify(day_number, actual)
+    }
 
-    enum TestError {
-        Failure(Part, String, String),
-        NoInputFile(String),
-        NoResult,
+    /// Asserts both outputs are equal, listing every part that differs
+    pub fn assert_day_output_eq(expected: &DayOutput, actual: &DayOutput) {
+        let part_count = expected.part_count().max(actual.part_count());
+
+        let differences: Vec<String> = (1..=part_count)
+            .filter(|n| expected.part(*n) != actual.part(*n))
+            .map(|n| {
+                format!(
+                    "{}: expected {:?}, got {:?}",
+                    Part(n),
+                    expected.part(n),
+                    actual.part(n)
+                )
+            })
+            .collect();
+
+        assert!(differences.is_empty(), "{}", differences.join("\n"));
     }
 
-    impl From<TestError> for String {
-        fn from(value: TestError) -> Self {
-            match value {
-                TestError::Failure(part, expected, actual) => {
-                    format!("{part} Expected {expected} got {actual}")
+    #[test]
+    fn examples_solve() {
+        let mut without_file = vec![];
+
+        for (day_number, solution, example) in days_with_examples() {
+            let mut inputs = vec![("embedded example", example.to_owned())];
+            match read_file(format!("./data/example/day{day_number}.txt").as_ref()) {
+                Ok(input) => inputs.push(("example file", input)),
+                Err(_) => without_file.push(day_number),
+            }
+
+            for (source, input) in inputs {
+                let input = prepare_input(day_number, input);
+                if let Err(e) = solution(&input) {
+                    panic!("Day {day_number} failed on its {source}: {}", e.0);
                 }
-                TestError::NoResult => "No result".to_owned(),
-                TestError::NoInputFile(s) => format!("No input file {s}"),
             }
         }
+
+        if !without_file.is_empty() {
+            println!("No example file for days {without_file:?}, only their embedded examples were solved");
+        }
     }
 
-    enum NoSolutionError {
-        NoFile,
-        ParseFailure,
+    #[test]
+    fn input_from_path() {
+        let path = std::env::temp_dir().join(format!("aoc-input-{}.txt", std::process::id()));
+        fs::write(&path, day1::EXAMPLE).unwrap();
+        let path = path.to_str().unwrap();
+
+        let input = read_input_from(path).ok().unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(input, day1::EXAMPLE);
+
+        let missing = read_input_from(path).err().unwrap();
+        assert_eq!(missing.path, path);
     }
 
-    fn get_solution(day_number: i32) -> Result<DayOutput, NoSolutionError> {
-        let path = format!("./data/solution/day{day_number}.txt");
+    #[test]
+    fn solutions_cover_every_day_in_order() {
+        let day_numbers: Vec<i32> = days().map(|(n, _)| n).collect();
+        assert_eq!(day_numbers, (1..=17).collect::<Vec<_>>());
+    }
 
-        let file = read_file(&path).map_err(|_| NoSolutionError::NoFile)?;
+    #[test]
+    fn prepare_input_normalizes() {
+        assert_eq!(prepare_input(2, "A Y \nB X\n\n".to_owned()), "A Y\nB X\n");
+        assert_eq!(prepare_input(5, "    [D] \n".to_owned()), "    [D] \n");
+    }
 
-        let doe = DayOutput::try_from(file.lines().next().ok_or(NoSolutionError::ParseFailure)?)
-            .map_err(|_| NoSolutionError::ParseFailure)?;
+    #[test]
+    fn timing_stats_min_and_mean() {
+        let durations = [
+            time::Duration::from_millis(30),
+            time::Duration::from_millis(10),
+            time::Duration::from_millis(20),
+            time::Duration::from_millis(40),
+        ];
+
+        assert_eq!(
+            timing_stats(&durations),
+            Some((
+                time::Duration::from_millis(10),
+                time::Duration::from_millis(25)
+            ))
+        );
+        assert_eq!(timing_stats(&[]), None);
+    }
 
-        Ok(doe)
+    #[test]
+    fn compare_matching() {
+        let expected = DayOutput::try_from("24000,45000").unwrap();
+        let actual = DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(45000)));
+
+        assert!(compare_output(expected, actual).is_ok());
     }
 
-    fn compare_result(
-        expected: Option<PartResult>,
-        actual: Option<PartResult>,
-        part: Part,
-    ) -> Result<(), TestError> {
-        let e = expected.ok_or(TestError::NoResult)?;
-        let i = actual.ok_or(TestError::NoResult)?;
+    #[test]
+    fn compare_mismatching() {
+        let expected = DayOutput::try_from("24000,45000").unwrap();
+        let actual = DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(41000)));
+
+        let err: String = compare_output(expected, actual).unwrap_err().into();
+        assert_eq!(err, "Part 2 Expected 45000 got 41000");
+    }
+
+    #[test]
+    fn diff_formatting() {
+        let expected = DayOutput::try_from("24000,45000").unwrap();
+        let actual = DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(41000)));
+
+        assert_eq!(
+            diff_lines(&expected, &actual),
+            vec![
+                "Part 1: OK".to_owned(),
+                "\x1b[31mPart 2: MISMATCH expected=45000 actual=41000\x1b[0m".to_owned()
+            ]
+        );
+
+        let actual = DayOutput::two(Some(PartResult::Int(24000)), None);
+        assert_eq!(
+            diff_lines(&expected, &actual)[1],
+            "\x1b[31mPart 2: MISMATCH expected=45000 actual=<MISSING>\x1b[0m"
+        );
+    }
 
-        match e.to_string() == i.to_string() {
-            // Ideally we'd decode types and check those, but this works fine
-            true => Ok(()),
-            false => Err(TestError::Failure(part, e.to_string(), i.to_string())),
+    #[test]
+    fn verify_missing_solution() {
+        let actual = DayOutput::two(None, None);
+
+        assert_eq!(verify(0, actual), Err("No solution file".to_owned()));
+    }
+
+    #[test]
+    fn verify_against_solution_file() {
+        // A day far past the puzzle's, so no real solution file is touched
+        let day_number = 9999;
+        let dir = std::path::Path::new("./data/solution");
+        let created_dir = !dir.exists();
+        fs::create_dir_all(dir).unwrap();
+        let path = dir.join(format!("day{day_number}.txt"));
+        fs::write(&path, "24000,45000\n").unwrap();
+
+        let matching = verify(
+            day_number,
+            DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(45000))),
+        );
+        let mismatching = verify(
+            day_number,
+            DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(41000))),
+        );
+
+        fs::remove_file(&path).unwrap();
+        if created_dir {
+            // Only removes directories left empty, anything else in data stays
+            let _ = fs::remove_dir(dir).and_then(|_| fs::remove_dir("./data"));
         }
+
+        assert_eq!(matching, Ok(()));
+        assert_eq!(
+            mismatching,
+            Err("Part 2 Expected 45000 got 41000".to_owned())
+        );
     }
 
-    pub fn test_day(day_number: i32, solution: DayFn) -> Result<(), String> {
-        let input =
-            get_input(day_number).map_err(|file_error| TestError::NoInputFile(file_error.path))?;
-        let expected = get_solution(day_number).map_err(|_| "Error getting solution")?;
-        let actual = solution(&input).map_err(|e| e.0)?;
+    #[test]
+    fn part_result_as_i64() {
+        let small = PartResult::Int(-5);
+        let big = PartResult::UInt(5_000_000_000);
+
+        assert!(small.as_i64() < big.as_i64());
+        assert_eq!(big.as_i64(), Some(5_000_000_000));
+        assert_eq!(PartResult::Str("42".to_owned()).as_i64(), Some(42));
+        assert_eq!(PartResult::Str("CMZ".to_owned()).as_i64(), None);
+        assert_eq!(PartResult::UInt(u64::MAX).as_i64(), None);
+    }
+
+    #[test]
+    fn day_output_eq() {
+        let output = DayOutput::two(Some(PartResult::Int(1)), Some(PartResult::Int(2)));
+        assert_day_output_eq(&output, &output);
+
+        let other = DayOutput::two(Some(PartResult::Int(1)), Some(PartResult::Int(3)));
+        let panic = std::panic::catch_unwind(|| assert_day_output_eq(&output, &other)).unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().unwrap(),
+            "Part 2: expected Some(Int(2)), got Some(Int(3))"
+        );
+    }
+
+    #[test]
+    fn solve_within_timeout() {
+        let fast: DayFn = |_| Ok(DayOutput::two(Some(PartResult::Int(1)), None));
+        let (output, _) = time_solution_with_timeout(fast, "", time::Duration::from_secs(10));
+        assert_eq!(output.ok().unwrap().to_string(), "1|None");
+
+        let slow: DayFn = |_| {
+            thread::sleep(time::Duration::from_millis(500));
+            Ok(DayOutput::two(None, None))
+        };
+        let (output, _) = time_solution_with_timeout(slow, "", time::Duration::from_millis(10));
+        assert_eq!(output.err().unwrap().0, "timed out");
+    }
+
+    #[test]
+    fn timing_line() {
+        let output = SolutionOutput {
+            values: DayOutput::two(Some(PartResult::Int(1)), None),
+            duration: time::Duration::from_millis(42),
+            day_number: 7,
+            input_bytes: 0,
+            input_lines: 0,
+        };
+
+        assert_eq!(format_timing(&output), "Day  7:    42ms");
+    }
+
+    #[test]
+    fn input_size() {
+        let output = run_input(1, day1::solve, "1\n2\n\n3\n\n4\n".to_owned(), None)
+            .ok()
+            .unwrap();
+
+        assert_eq!(output.input_bytes, 10);
+        assert_eq!(output.input_lines, 6);
+        assert_eq!(format_input_size(&output), "(6 lines, 10 bytes)");
+    }
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(format_duration(time::Duration::from_micros(0)), "0µs");
+        assert_eq!(format_duration(time::Duration::from_micros(850)), "850µs");
+        assert_eq!(format_duration(time::Duration::from_micros(1000)), "1ms");
+        assert_eq!(format_duration(time::Duration::from_micros(12_400)), "12ms");
+        assert_eq!(format_duration(time::Duration::from_millis(999)), "999ms");
+        assert_eq!(format_duration(time::Duration::from_millis(1000)), "1.0s");
+        assert_eq!(format_duration(time::Duration::from_millis(3420)), "3.4s");
+    }
+
+    #[test]
+    fn three_parts() {
+        let expected = DayOutput::try_from("1,2,3").unwrap();
+        let actual = DayOutput::new(vec![
+            Some(PartResult::Int(1)),
+            Some(PartResult::Int(2)),
+            Some(PartResult::Int(3)),
+        ]);
+
+        assert_eq!(actual.to_string(), "1|2|3");
+        assert!(compare_output(expected, actual).is_ok());
 
-        compare_result(expected.part1, actual.part1, Part::Part1)?;
-        compare_result(expected.part2, actual.part2, Part::Part2)?;
+        let expected = DayOutput::try_from("1,2,3").unwrap();
+        let actual = DayOutput::two(Some(PartResult::Int(1)), Some(PartResult::Int(2)));
 
-        Ok(())
+        let err: String = compare_output(expected, actual).unwrap_err().into();
+        assert_eq!(err, "No result");
     }
 }
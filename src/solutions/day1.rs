@@ -1,19 +1,46 @@
+use crate::parsing::blocks;
 use crate::solutions::DayOutput;
 use crate::solutions::PartResult;
 
-use super::LogicError;
+use super::{LogicError, Solution};
+
+/// The calories carried by each elf, erroring on any line that isn't a number
+fn parse_elves(input: &str) -> Result<Vec<Vec<i32>>, LogicError> {
+    blocks(input)
+        .map(|elf| {
+            elf.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    line.trim()
+                        .parse::<i32>()
+                        .map_err(|_| LogicError(format!("Invalid calorie line '{line}'")))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub(crate) const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
 
 // https://adventofcode.com/2022/day/1
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let elfs: Vec<&str> = input.split("\n\n").collect();
-
-    let mut elf_calories = elfs
+    let mut elf_calories = parse_elves(input)?
         .iter()
-        .map(|line| {
-            str::split(line, "\n")
-                .filter_map(|line| line.parse::<i32>().ok())
-                .sum::<i32>()
-        })
+        .map(|elf| elf.iter().sum::<i32>())
         .collect::<Vec<i32>>();
 
     let max_elf_calories = *(elf_calories.iter().max().expect("Valid sum"));
@@ -22,13 +49,51 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
 
     let top3_elf_calories = elf_calories[0..3].iter().sum();
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(max_elf_calories)),
-        part2: Some(PartResult::Int(top3_elf_calories)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(max_elf_calories)),
+        Some(PartResult::Int(top3_elf_calories)),
+    ))
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use crate::solutions::tests::assert_day_output_eq;
+    use crate::solutions::{DayOutput, PartResult};
+
+    #[test]
+    fn example() {
+        let output = super::solve(super::EXAMPLE).ok().unwrap();
+
+        assert_day_output_eq(
+            &DayOutput::two(Some(PartResult::Int(24000)), Some(PartResult::Int(45000))),
+            &output,
+        );
+    }
+
+    #[test]
+    fn garbage_line() {
+        let err = super::parse_elves("1000\n2000\n\n3000\nabc\n4000\n").unwrap_err();
+        assert_eq!(err.0, "Invalid calorie line 'abc'");
+
+        assert_eq!(
+            super::parse_elves("1000\n2000\n\n3000\n").ok().unwrap(),
+            vec![vec![1000, 2000], vec![3000]]
+        );
+    }
 
     #[test]
     fn day() -> Result<(), String> {
@@ -3,7 +3,7 @@ use std::{
     str::FromStr,
 };
 
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 enum Instruction {
     Noop,
@@ -55,9 +55,14 @@ impl Display for Crt {
 }
 
 impl Crt {
+    // Cycles past the last pixel fall outside the frame and are not drawn
     fn draw(&mut self, cpu: &Cpu) {
+        let Some(pixel) = self.screen.get_mut(cpu.cycle_count) else {
+            return;
+        };
+
         if ((cpu.cycle_count % CRT_WIDTH) as i32).abs_diff(cpu.register) <= 1 {
-            self.screen[cpu.cycle_count] = true;
+            *pixel = true;
         }
     }
 }
@@ -115,105 +120,7 @@ impl Cpu {
     }
 }
 
-// https://adventofcode.com/2022/day/10
-pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let signal_sum = get_signal_strength(input);
-    let _ = crt_message(input);
-
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(signal_sum)),
-        part2: Some(PartResult::Str("it works okay".to_owned())),
-    })
-}
-
-fn crt_message(input: &str) -> String {
-    let mut cpu = Cpu::new_with_program(
-        input
-            .lines()
-            .map(|line| line.parse::<Instruction>().unwrap()),
-    );
-    let mut crt = Crt {
-        screen: [false; CRT_ROWS * CRT_WIDTH],
-    };
-
-    while !cpu.is_done() {
-        cpu.cycle();
-        crt.draw(&cpu);
-    }
-
-    crt.to_string()
-}
-
-fn get_signal_strength(input: &str) -> i32 {
-    let mut cpu = Cpu::new_with_program(
-        input
-            .lines()
-            .map(|line| line.parse::<Instruction>().unwrap()),
-    );
-
-    let mut signal_sum = 0;
-    cpu.run_to_count(19);
-    // 20
-    signal_sum += cpu.signal_strenght();
-
-    cpu.cycle_times(40);
-    // 60
-    signal_sum += cpu.signal_strenght();
-
-    cpu.cycle_times(40);
-    // 100
-    signal_sum += cpu.signal_strenght();
-
-    cpu.cycle_times(40);
-    //140
-    signal_sum += cpu.signal_strenght();
-
-    cpu.cycle_times(40);
-    // 180
-    signal_sum += cpu.signal_strenght();
-
-    cpu.cycle_times(40);
-    // 220
-    signal_sum += cpu.signal_strenght();
-    signal_sum
-}
-
-#[cfg(test)]
-mod tests {
-    use super::Cpu;
-
-    #[test]
-    fn day() -> Result<(), String> {
-        super::super::tests::test_day(10, super::solve)
-    }
-
-    #[test]
-    fn example_1() -> Result<(), String> {
-        let input = ["noop", "addx 3", "addx -5"];
-
-        let mut cpu = Cpu::new_with_program(input.iter().map(|line| line.parse().unwrap()));
-
-        cpu.cycle(); //1st
-        assert_eq!(cpu.register, 1);
-
-        cpu.cycle(); //2nd
-        assert_eq!(cpu.register, 1);
-
-        cpu.cycle(); //3rd
-        assert_eq!(cpu.register, 4);
-
-        cpu.cycle(); //4rd
-        assert_eq!(cpu.register, 4);
-
-        cpu.cycle(); //5th
-        assert_eq!(cpu.register, -1);
-
-        Ok(())
-    }
-
-    #[test]
-    fn example_2() -> Result<(), String> {
-        let input: String = "addx 15
+pub(crate) const EXAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -358,8 +265,135 @@ addx -6
 addx -11
 noop
 noop
-noop"
-            .to_owned();
+noop
+";
+
+// https://adventofcode.com/2022/day/10
+pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
+    let signal_sum = get_signal_strength(input);
+    let _ = crt_message(input);
+
+    Ok(DayOutput::two(
+        Some(PartResult::Int(signal_sum)),
+        Some(PartResult::Str("it works okay".to_owned())),
+    ))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
+}
+
+fn crt_message(input: &str) -> String {
+    let mut cpu = Cpu::new_with_program(
+        input
+            .lines()
+            .map(|line| line.parse::<Instruction>().unwrap()),
+    );
+    let mut crt = Crt {
+        screen: [false; CRT_ROWS * CRT_WIDTH],
+    };
+
+    while !cpu.is_done() {
+        cpu.cycle();
+        crt.draw(&cpu);
+    }
+
+    crt.to_string()
+}
+
+fn get_signal_strength(input: &str) -> i32 {
+    let mut cpu = Cpu::new_with_program(
+        input
+            .lines()
+            .map(|line| line.parse::<Instruction>().unwrap()),
+    );
+
+    let mut signal_sum = 0;
+    cpu.run_to_count(19);
+    // 20
+    signal_sum += cpu.signal_strenght();
+
+    cpu.cycle_times(40);
+    // 60
+    signal_sum += cpu.signal_strenght();
+
+    cpu.cycle_times(40);
+    // 100
+    signal_sum += cpu.signal_strenght();
+
+    cpu.cycle_times(40);
+    //140
+    signal_sum += cpu.signal_strenght();
+
+    cpu.cycle_times(40);
+    // 180
+    signal_sum += cpu.signal_strenght();
+
+    cpu.cycle_times(40);
+    // 220
+    signal_sum += cpu.signal_strenght();
+    signal_sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cpu, CRT_ROWS, CRT_WIDTH, EXAMPLE};
+
+    #[test]
+    fn day() -> Result<(), String> {
+        super::super::tests::test_day(10, super::solve)
+    }
+
+    #[test]
+    fn example_1() -> Result<(), String> {
+        let input = ["noop", "addx 3", "addx -5"];
+
+        let mut cpu = Cpu::new_with_program(input.iter().map(|line| line.parse().unwrap()));
+
+        cpu.cycle(); //1st
+        assert_eq!(cpu.register, 1);
+
+        cpu.cycle(); //2nd
+        assert_eq!(cpu.register, 1);
+
+        cpu.cycle(); //3rd
+        assert_eq!(cpu.register, 4);
+
+        cpu.cycle(); //4rd
+        assert_eq!(cpu.register, 4);
+
+        cpu.cycle(); //5th
+        assert_eq!(cpu.register, -1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn draw_beyond_frame() {
+        let input = vec!["noop"; CRT_ROWS * CRT_WIDTH + 60].join("\n");
+
+        let message = super::crt_message(&input);
+
+        assert_eq!(message.lines().filter(|l| !l.is_empty()).count(), CRT_ROWS);
+        assert!(message
+            .lines()
+            .filter(|l| !l.is_empty())
+            .all(|l| l.len() == CRT_WIDTH));
+    }
+
+    #[test]
+    fn example_2() -> Result<(), String> {
+        let input = EXAMPLE;
 
         let mut cpu = Cpu::new_with_program(input.lines().map(|line| line.parse().unwrap()));
 
@@ -1,7 +1,17 @@
 use std::collections::VecDeque;
 use std::str::FromStr;
 
-use super::{DayOutput, LogicError, PartResult};
+use crate::parsing::blocks;
+
+use super::{DayOutput, LogicError, PartResult, Solution};
+
+// Part 1 has no modulus to keep worry levels small, squaring a big item can outgrow u64 before the division by 3
+type WorryLevel = u128;
+
+// Part 1 worry levels are divided by this after every inspection
+const RELIEF_DIVISOR: WorryLevel = 3;
+const P1_ROUNDS: usize = 20;
+const P2_ROUNDS: usize = 10_000;
 
 #[derive(Clone)]
 enum Operator {
@@ -11,7 +21,7 @@ enum Operator {
 
 #[derive(Clone)]
 enum Operand {
-    Literal(u64),
+    Literal(WorryLevel),
     Old,
 }
 
@@ -42,16 +52,11 @@ impl FromStr for Operator {
 }
 
 struct Monkey {
-    items: VecDeque<u64>,
+    items: VecDeque<WorryLevel>,
     behaviour: MonkeyBehaviour,
     items_processed: u32,
 }
 
-struct ItemThrow {
-    items: Vec<u64>,
-    target: u32,
-}
-
 impl Monkey {
     fn new(behaviour: MonkeyBehaviour) -> Self {
         Self {
@@ -61,84 +66,55 @@ impl Monkey {
         }
     }
 
-    fn take_turn_p1(&mut self, false_throw: &mut ItemThrow, true_throw: &mut ItemThrow) {
-        false_throw.target = self.behaviour.false_target;
-        true_throw.target = self.behaviour.true_target;
-
-        while !self.items.is_empty() {
-            let item = self
-                .items
-                .pop_front()
-                .expect("Queue to stop before it empties");
-
-            let item = self.worry_level_operation(item);
-
-            let item = item / 3;
-
-            let is_divisable = (item % self.behaviour.test_div) == 0;
-
-            if is_divisable {
-                true_throw.items.push(item);
-            } else {
-                false_throw.items.push(item);
-            }
-
-            self.items_processed += 1;
-        }
+    /// Inspects every held item, returning each throw as (target, item)
+    /// relieve is applied to the worry level after the monkey's operation
+    fn take_turn(
+        &mut self,
+        relieve: impl Fn(WorryLevel) -> WorryLevel,
+    ) -> Vec<(usize, WorryLevel)> {
+        let items = std::mem::take(&mut self.items);
+        self.items_processed += items.len() as u32;
+
+        items
+            .into_iter()
+            .map(|item| {
+                let item = relieve(self.worry_level_operation(item));
+                (self.throw_target(item), item)
+            })
+            .collect()
     }
 
-    fn take_turn_p2(&mut self, false_throw: &mut ItemThrow, true_throw: &mut ItemThrow, c: u64) {
-        false_throw.target = self.behaviour.false_target;
-        true_throw.target = self.behaviour.true_target;
-
-        while !self.items.is_empty() {
-            let item = self
-                .items
-                .pop_front()
-                .expect("Queue to stop before it empties");
-
-            let item = self.worry_level_operation(item);
-
-            let item = item % c;
-
-            let is_divisable = (item % self.behaviour.test_div) == 0;
-
-            if is_divisable {
-                true_throw.items.push(item);
-            } else {
-                false_throw.items.push(item);
-            }
+    fn throw_target(&self, item: WorryLevel) -> usize {
+        let target = if item % self.behaviour.test_div == 0 {
+            self.behaviour.true_target
+        } else {
+            self.behaviour.false_target
+        };
 
-            self.items_processed += 1;
-        }
+        target as usize
     }
-    fn worry_level_operation(&self, level: u64) -> u64 {
+
+    fn worry_level_operation(&self, level: WorryLevel) -> WorryLevel {
         let operand = match self.behaviour.operation_operand {
             Operand::Literal(n) => n,
             Operand::Old => level,
         };
 
         match self.behaviour.operation_operator {
-            Operator::Add => level + operand,
-            Operator::Multiply => level * operand,
+            Operator::Add => level.checked_add(operand),
+            Operator::Multiply => level.checked_mul(operand),
         }
-    }
-
-    fn receive_items(&mut self, throw: &mut ItemThrow) {
-        throw
-            .items
-            .iter()
-            .for_each(|item| self.items.push_back(*item));
+        .expect("Worry level should fit in a u128")
     }
 }
 
 /// Stateless monkey settings
 #[derive(Clone)]
 struct MonkeyBehaviour {
-    starting_items: Vec<u64>,
+    starting_items: Vec<WorryLevel>,
     operation_operator: Operator,
     operation_operand: Operand,
-    test_div: u64,
+    test_div: WorryLevel,
     true_target: u32,
     false_target: u32,
 }
@@ -154,12 +130,10 @@ fn get_num_from_char_iter(iter: impl Iterator<Item = char>) -> u32 {
 
 struct MonkeyGame {
     monkeys: Vec<Monkey>,
-    true_trow: ItemThrow,
-    false_throw: ItemThrow,
-    g: u64,
+    g: WorryLevel,
 }
 
-fn gcd(iter: impl Iterator<Item = u64>) -> u64 {
+fn gcd(iter: impl Iterator<Item = WorryLevel>) -> WorryLevel {
     iter.reduce(|a, b| a * b).unwrap()
 }
 
@@ -167,78 +141,27 @@ impl MonkeyGame {
     fn new(monkeys: Vec<Monkey>) -> Self {
         let g = gcd(monkeys.iter().map(|m| m.behaviour.test_div));
 
-        Self {
-            true_trow: ItemThrow {
-                items: Vec::new(),
-                target: 0,
-            },
-            false_throw: ItemThrow {
-                items: Vec::new(),
-                target: 0,
-            },
-            monkeys,
-            g,
-        }
+        Self { monkeys, g }
     }
 
+    /// Lets every monkey take a turn
+    /// Thrown items are delivered after the thrower's turn, so a monkey throwing to itself holds on to the item until its next turn
     fn run_round(&mut self, part: Part) {
-        match part {
-            Part::Part1 => {
-                for i in 0..self.monkeys.len() {
-                    self.monkeys
-                        .get_mut(i)
-                        .unwrap()
-                        .take_turn_p1(&mut self.false_throw, &mut self.true_trow);
-                    {
-                        let true_monkey = self
-                            .monkeys
-                            .get_mut(self.true_trow.target as usize)
-                            .unwrap();
-
-                        true_monkey.receive_items(&mut self.true_trow);
-                        self.true_trow.items.clear();
-                    }
-                    {
-                        let false_monkey = self
-                            .monkeys
-                            .get_mut(self.false_throw.target as usize)
-                            .unwrap();
-
-                        false_monkey.receive_items(&mut self.false_throw);
-                        self.false_throw.items.clear();
-                    }
+        for i in 0..self.monkeys.len() {
+            let throws = match part {
+                Part::Part1 { relief_divisor } => {
+                    self.monkeys[i].take_turn(|item| item / relief_divisor)
                 }
-            }
-            Part::Part2 => {
-                for i in 0..self.monkeys.len() {
-                    self.monkeys.get_mut(i).unwrap().take_turn_p2(
-                        &mut self.false_throw,
-                        &mut self.true_trow,
-                        self.g,
-                    );
-                    {
-                        let true_monkey = self
-                            .monkeys
-                            .get_mut(self.true_trow.target as usize)
-                            .unwrap();
-
-                        true_monkey.receive_items(&mut self.true_trow);
-                        self.true_trow.items.clear();
-                    }
-                    {
-                        let false_monkey = self
-                            .monkeys
-                            .get_mut(self.false_throw.target as usize)
-                            .unwrap();
-
-                        false_monkey.receive_items(&mut self.false_throw);
-                        self.false_throw.items.clear();
-                    }
+                Part::Part2 => {
+                    let g = self.g;
+                    self.monkeys[i].take_turn(|item| item % g)
                 }
+            };
+
+            for (target, item) in throws {
+                self.monkeys[target].items.push_back(item);
             }
         }
-
-        // for monkey in self.monkeys.iter_mut() {}
     }
 
     fn monkey_business(&self) -> u64 {
@@ -288,7 +211,7 @@ impl FromStr for MonkeyBehaviour {
             starting_items,
             operation_operator: operator,
             operation_operand: operand,
-            test_div: u64::from(divider),
+            test_div: WorryLevel::from(divider),
             true_target,
             false_target,
         })
@@ -297,39 +220,203 @@ impl FromStr for MonkeyBehaviour {
     }
 }
 
+// Part 1 divides worry levels by the relief divisor, part 2 keeps them in check with a modulus instead
+#[derive(Clone, Copy)]
 enum Part {
-    Part1,
+    Part1 { relief_divisor: WorryLevel },
     Part2,
 }
 
+// The puzzle's part 1
+const PART_1: Part = Part::Part1 {
+    relief_divisor: RELIEF_DIVISOR,
+};
+
+/// Plays a fresh game for the given number of rounds
+fn play(behaviours: &[MonkeyBehaviour], part: Part, rounds: usize) -> MonkeyGame {
+    let mut game = MonkeyGame::new(behaviours.iter().cloned().map(Monkey::new).collect());
+
+    for _ in 0..rounds {
+        game.run_round(part);
+    }
+
+    game
+}
+
+pub(crate) const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
 // https://adventofcode.com/2022/day/11
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let behaviours: Vec<_> = input
-        .split("\n\n")
+    let behaviours: Vec<_> = blocks(input)
         .map(|str| str.parse::<MonkeyBehaviour>().unwrap())
         .collect();
 
-    let mut p1_game = MonkeyGame::new(behaviours.clone().into_iter().map(Monkey::new).collect());
-    let mut p2_game = MonkeyGame::new(behaviours.into_iter().map(Monkey::new).collect());
+    let p1_game = play(&behaviours, PART_1, P1_ROUNDS);
+    let p2_game = play(&behaviours, Part::Part2, P2_ROUNDS);
 
-    for _ in 0..20 {
-        p1_game.run_round(Part::Part1);
-    }
-    for _ in 0..10_000 {
-        p2_game.run_round(Part::Part2);
+    Ok(DayOutput::two(
+        Some(PartResult::UInt(p1_game.monkey_business())),
+        Some(PartResult::UInt(p2_game.monkey_business())),
+    ))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
     }
 
-    Ok(DayOutput {
-        part1: Some(PartResult::UInt(p1_game.monkey_business())),
-        part2: Some(PartResult::UInt(p2_game.monkey_business())),
-    })
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::parsing::blocks;
+
+    use super::{
+        play, Monkey, MonkeyBehaviour, MonkeyGame, Operand, Operator, Part, EXAMPLE, PART_1,
+    };
+
+    fn items_processed(game: &MonkeyGame) -> Vec<u32> {
+        game.monkeys.iter().map(|m| m.items_processed).collect()
+    }
 
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(11, super::solve)
     }
+
+    #[test]
+    fn square_large_item() {
+        // 5e9 squared is beyond u64::MAX
+        let squarer = MonkeyBehaviour {
+            starting_items: vec![5_000_000_000],
+            operation_operator: Operator::Multiply,
+            operation_operand: Operand::Old,
+            test_div: 2,
+            true_target: 1,
+            false_target: 1,
+        };
+        let holder = MonkeyBehaviour {
+            starting_items: vec![],
+            operation_operator: Operator::Add,
+            operation_operand: Operand::Literal(1),
+            test_div: 2,
+            true_target: 1,
+            false_target: 1,
+        };
+
+        let mut game = MonkeyGame::new(vec![Monkey::new(squarer), Monkey::new(holder)]);
+        for _ in 0..20 {
+            game.run_round(PART_1);
+        }
+
+        assert_eq!(game.monkeys[0].items_processed, 1);
+        assert_eq!(game.monkeys[1].items_processed, 20);
+        assert_eq!(game.monkey_business(), 20);
+    }
+
+    #[test]
+    fn throw_to_self() {
+        let self_thrower = MonkeyBehaviour {
+            starting_items: vec![6],
+            operation_operator: Operator::Add,
+            operation_operand: Operand::Literal(0),
+            test_div: 2,
+            true_target: 0,
+            false_target: 1,
+        };
+        let idle = MonkeyBehaviour {
+            starting_items: vec![],
+            operation_operator: Operator::Add,
+            operation_operand: Operand::Literal(0),
+            test_div: 2,
+            true_target: 0,
+            false_target: 0,
+        };
+
+        let mut game = MonkeyGame::new(vec![Monkey::new(self_thrower), Monkey::new(idle)]);
+
+        // 6 / 3 = 2 is divisible by 2, the item is thrown back to monkey 0 and kept for the next round
+        game.run_round(PART_1);
+        assert_eq!(game.monkeys[0].items, [2]);
+        assert_eq!(game.monkeys[0].items_processed, 1);
+
+        // 2 / 3 = 0 is divisible as well, still only inspected once per round
+        game.run_round(PART_1);
+        assert_eq!(game.monkeys[0].items, [0]);
+        assert_eq!(game.monkeys[0].items_processed, 2);
+        assert_eq!(game.monkeys[1].items_processed, 0);
+    }
+
+    #[test]
+    fn example_single_round() {
+        let behaviours: Vec<MonkeyBehaviour> =
+            blocks(EXAMPLE).map(|b| b.parse().unwrap()).collect();
+
+        let game = play(&behaviours, PART_1, 1);
+        assert_eq!(items_processed(&game), [2, 4, 3, 5]);
+        assert_eq!(game.monkeys[0].items, [20, 23, 27, 26]);
+
+        let game = play(&behaviours, Part::Part2, 1);
+        assert_eq!(items_processed(&game), [2, 4, 3, 6]);
+
+        // Without relief nothing becomes divisible, everything ends up at monkey 1 unreduced
+        let game = play(&behaviours, Part::Part1 { relief_divisor: 1 }, 1);
+        assert_eq!(items_processed(&game), [2, 4, 3, 6]);
+        assert_eq!(game.monkeys[1].items, [77, 1504, 1865, 6244, 3603, 9412]);
+    }
+
+    #[test]
+    fn example_items_after_round() {
+        let behaviours: Vec<MonkeyBehaviour> =
+            blocks(EXAMPLE).map(|b| b.parse().unwrap()).collect();
+
+        let game = play(&behaviours, PART_1, 1);
+        assert_eq!(game.monkeys[1].items, [2080, 25, 167, 207, 401, 1046]);
+        assert!(game.monkeys[2].items.is_empty());
+        assert!(game.monkeys[3].items.is_empty());
+
+        let game = play(&behaviours, PART_1, 2);
+        assert_eq!(game.monkeys[0].items, [695, 10, 71, 135, 350]);
+        assert_eq!(game.monkeys[1].items, [43, 49, 58, 55, 362]);
+
+        let game = play(&behaviours, PART_1, 20);
+        assert_eq!(game.monkeys[0].items, [10, 12, 14, 26, 34]);
+        assert_eq!(game.monkeys[1].items, [245, 93, 53, 199, 115]);
+        assert!(game.monkeys[2].items.is_empty());
+        assert!(game.monkeys[3].items.is_empty());
+    }
 }
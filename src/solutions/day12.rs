@@ -6,10 +6,7 @@ use std::{
 
 use crate::{grid::Grid, vec2d::Vec2D};
 
-use super::{DayOutput, LogicError, PartResult};
-
-const START_MARKER: u8 = b'S';
-const END_MARKER: u8 = b'E';
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 const VISUALIZE_PART_1: bool = false;
 const INTERACTIVE_PART_2: bool = false;
@@ -31,25 +28,49 @@ fn retrace_path(mut closed_set: HashMap<Vec2D<i32>, Node>, last_node: &Node) ->
     }
 }
 
-fn fix_marker_elevations(n: &u8) -> u8 {
-    match n {
-        b'S' => b'a',
-        b'E' => b'z',
-        _ => *n,
+// The characters marking the start and end of the climb
+#[derive(Clone, Copy)]
+struct Markers {
+    start: u8,
+    end: u8,
+}
+
+impl Markers {
+    // The start sits at elevation a and the end at elevation z
+    fn elevation(&self, n: &u8) -> u8 {
+        match *n {
+            n if n == self.start => b'a',
+            n if n == self.end => b'z',
+            n => n,
+        }
     }
 }
 
-// Find path from marker E to any 'a' using bfs
-fn find_path_down(map: &Grid<u8>) -> usize {
+// Shortest climb from any cell at elevation a to the end marker, searching from all of them at once
+fn find_shortest_climb(map: &Grid<u8>, markers: Markers) -> usize {
+    let sources: Vec<Vec2D<i32>> = map
+        .positions_where(|b| markers.elevation(b) == b'a')
+        .collect();
+    let end_pos = find_marker_position(map, markers.end).expect("Should find end marker position");
+
+    let distances = map.bfs_from_sources(&sources, |from, to| {
+        markers.elevation(to) <= markers.elevation(from) + 1
+    });
+
+    distances
+        .get_by_vec(&end_pos)
+        .copied()
+        .flatten()
+        .expect("No path found") as usize
+}
+
+// Find path from the end marker to any 'a' using bfs
+fn find_path_down(map: &Grid<u8>, markers: Markers) -> usize {
     let mut frontier: BinaryHeap<BFSNode> = BinaryHeap::new();
     let mut closed_set: HashMap<Vec2D<i32>, BFSNode> = HashMap::new();
 
-    let start_pos = find_unique_character_index(map, END_MARKER)
-        .map(|index| {
-            map.position_of_index(index)
-                .expect("Should find start marker index")
-        })
-        .expect("Should find start marker position");
+    let start_pos =
+        find_marker_position(map, markers.end).expect("Should find end marker position");
 
     let start_node = BFSNode {
         pos: start_pos,
@@ -64,7 +85,7 @@ fn find_path_down(map: &Grid<u8>) -> usize {
         let current_postion = node.pos;
         let current_elevation = map
             .get_by_vec(&current_postion)
-            .map(fix_marker_elevations)
+            .map(|n| markers.elevation(n))
             .expect("Position should be on grid");
 
         if current_elevation == b'a' {
@@ -84,7 +105,7 @@ fn find_path_down(map: &Grid<u8>) -> usize {
         neighbours.retain(|neighbour_position| {
             let new_elevation = map
                 .get_by_vec(neighbour_position)
-                .map(fix_marker_elevations) // Replace S and E with a and z
+                .map(|n| markers.elevation(n)) // Replace S and E with a and z
                 .unwrap();
 
             // Never allow a step that is too steep
@@ -180,25 +201,19 @@ fn print_with_coloring_p2(
     });
 }
 
-// Find path from marker S to marker E using a*
-fn find_path(map: &Grid<u8>) -> Vec<Vec2D<i32>> {
+// Find path from the start marker to the end marker using a*
+fn find_path(map: &Grid<u8>, markers: Markers) -> Vec<Vec2D<i32>> {
     let mut frontier: BinaryHeap<Node> = BinaryHeap::new();
     let mut closed_set: HashMap<Vec2D<i32>, Node> = HashMap::new();
 
-    let start_pos = find_unique_character_index(map, START_MARKER)
-        .map(|index| {
-            map.position_of_index(index)
-                .expect("Should find start marker index")
-        })
-        .expect("Should find start marker position");
+    let start_pos =
+        find_marker_position(map, markers.start).expect("Should find start marker position");
 
-    let end_pos = find_unique_character_index(map, END_MARKER)
-        .map(|index| {
-            map.position_of_index(index)
-                .expect("Should find end marker index")
-        })
-        .expect("Should find end marker position");
+    let end_pos = find_marker_position(map, markers.end).expect("Should find end marker position");
 
+    // Manhattan distance is admissible because movement is 4-connected and every step costs 1
+    // Elevation limits only remove steps, so the real path is never shorter than this estimate
+    // Allowing diagonal movement would make this overestimate and break optimality
     let hueristic = |position: &Vec2D<i32>| position.distance_manhatten(&end_pos);
 
     // let start_node =  create_node_for_position(pos, end_pos, parent: &node);
@@ -224,7 +239,7 @@ fn find_path(map: &Grid<u8>) -> Vec<Vec2D<i32>> {
         let current_position = node.pos;
         let current_elevation = map
             .get_by_vec(&current_position)
-            .map(fix_marker_elevations) // Fix start marker elevation
+            .map(|n| markers.elevation(n)) // Fix start marker elevation
             .expect("Valid position");
 
         let current_cost = node.cost_so_far.get();
@@ -237,7 +252,7 @@ fn find_path(map: &Grid<u8>) -> Vec<Vec2D<i32>> {
         neighbours.retain(|neighbour_position| {
             let new_elevation = map
                 .get_by_vec(neighbour_position)
-                .map(fix_marker_elevations) // Replace S and E with a and z
+                .map(|n| markers.elevation(n)) // Replace S and E with a and z
                 .unwrap();
 
             // Never allow a step that is too steep
@@ -289,8 +304,8 @@ fn find_path(map: &Grid<u8>) -> Vec<Vec2D<i32>> {
     panic!("Pathfinding failed")
 }
 
-fn find_unique_character_index(map: &Grid<u8>, marker: u8) -> Option<usize> {
-    map.iter().position(|b| *b == marker)
+fn find_marker_position(map: &Grid<u8>, marker: u8) -> Option<Vec2D<i32>> {
+    map.positions_where(|b| *b == marker).next()
 }
 
 #[derive(PartialEq, Eq, Clone)]
@@ -336,20 +351,50 @@ impl Ord for BFSNode {
     }
 }
 
+pub(crate) const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
 // https://adventofcode.com/2022/day/12
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let grid = Grid::from_str(input);
-    let p1_movements = find_path(&grid);
-    let p2_len = find_path_down(&grid);
+    let markers = Markers {
+        start: b'S',
+        end: b'E',
+    };
+    let p1_movements = find_path(&grid, markers);
+    // Only the search down from the end marker can be stepped through
+    let p2_len = if INTERACTIVE_PART_2 {
+        find_path_down(&grid, markers)
+    } else {
+        find_shortest_climb(&grid, markers)
+    };
 
     if VISUALIZE_PART_1 {
         print_with_coloring(&grid, &p1_movements);
     }
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(p1_movements.len() as i32)),
-        part2: Some(PartResult::Int(p2_len as i32)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(p1_movements.len() as i32)),
+        Some(PartResult::Int(p2_len as i32)),
+    ))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 fn print_with_coloring(grid: &Grid<u8>, path: &[Vec2D<i32>]) {
@@ -390,9 +435,48 @@ fn print_with_coloring(grid: &Grid<u8>, path: &[Vec2D<i32>]) {
 #[cfg(test)]
 mod tests {
 
-    use crate::{grid::Grid, solutions::day12::print_with_coloring};
+    use std::collections::{HashSet, VecDeque};
+
+    use crate::{grid::Grid, solutions::day12::print_with_coloring, vec2d::Vec2D};
+
+    use super::{
+        find_marker_position, find_path, find_path_down, find_shortest_climb, Markers, EXAMPLE,
+    };
+
+    const MARKERS: Markers = Markers {
+        start: b'S',
+        end: b'E',
+    };
+
+    // Plain breadth first search, a reference for the shortest path length
+    fn bfs_path_length(grid: &Grid<u8>) -> usize {
+        let start = find_marker_position(grid, MARKERS.start).unwrap();
+        let end = find_marker_position(grid, MARKERS.end).unwrap();
+
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        let mut neighbours: Vec<Vec2D<i32>> = vec![];
+
+        while let Some((pos, distance)) = queue.pop_front() {
+            if pos == end {
+                return distance;
+            }
+
+            let elevation = MARKERS.elevation(grid.get_by_vec(&pos).unwrap());
+
+            neighbours.clear();
+            grid.get_neighbours(pos, &mut neighbours);
+
+            for neighbour in &neighbours {
+                let new_elevation = MARKERS.elevation(grid.get_by_vec(neighbour).unwrap());
+                if new_elevation <= elevation + 1 && seen.insert(*neighbour) {
+                    queue.push_back((*neighbour, distance + 1));
+                }
+            }
+        }
 
-    use super::find_path;
+        panic!("No path found")
+    }
 
     #[test]
     fn day() -> Result<(), String> {
@@ -401,17 +485,61 @@ mod tests {
 
     #[test]
     fn example() {
-        let str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
-
-        let grid = Grid::from_str(str);
-        let movements = find_path(&grid);
+        let grid = Grid::from_str(EXAMPLE);
+        let movements = find_path(&grid, MARKERS);
 
         print_with_coloring(&grid, &movements);
 
         assert_eq!(movements.len(), 31);
+        assert_eq!(bfs_path_length(&grid), 31);
+    }
+
+    #[test]
+    fn heuristic_dead_end() {
+        // Heading right from S, towards E, is a long way around: down, right and back up to the ramp
+        // The short way starts by stepping away from E, up and along the top row
+        // A search trusting the heuristic too much would take the long way
+        let ramp: String = ('b'..='y').collect();
+        let wall = "z".repeat(ramp.len() + 1);
+
+        let str = [
+            format!("aaaaaaaaaaa{ramp}E"),
+            format!("azzzzzzzzza{wall}"),
+            format!("aSaaaaaaaza{wall}"),
+            format!("zzzzzzzzaza{wall}"),
+            format!("zzzzzzzzaza{wall}"),
+            format!("zzzzzzzzaza{wall}"),
+            format!("zzzzzzzzaaa{wall}"),
+        ]
+        .join("\n");
+
+        let grid = Grid::from_str(&str);
+        let movements = find_path(&grid, MARKERS);
+
+        // 13 steps to the foot of the ramp the short way, 19 the long way, then 25 up the ramp
+        assert_eq!(movements.len(), bfs_path_length(&grid));
+        assert_eq!(movements.len(), 38);
+    }
+
+    #[test]
+    fn custom_markers() {
+        let str = EXAMPLE.replace('S', "<").replace('E', ">");
+
+        let grid = Grid::from_str(&str);
+        let markers = Markers {
+            start: b'<',
+            end: b'>',
+        };
+
+        assert_eq!(find_path(&grid, markers).len(), 31);
+        assert_eq!(find_shortest_climb(&grid, markers), 29);
+    }
+
+    #[test]
+    fn example_shortest_climb() {
+        let grid = Grid::from_str(EXAMPLE);
+
+        assert_eq!(find_shortest_climb(&grid, MARKERS), 29);
+        assert_eq!(find_path_down(&grid, MARKERS), 29);
     }
 }
@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, iter::Peekable, str::FromStr};
 
-use super::{DayOutput, LogicError};
+use super::{DayOutput, LogicError, Solution};
 
 #[derive(Debug, PartialEq, Eq)]
 enum ListItem {
@@ -14,32 +14,35 @@ impl PartialOrd for ListItem {
     }
 }
 
-fn compare_lists(left_list: &Vec<ListItem>, right_list: &Vec<ListItem>) -> std::cmp::Ordering {
-    let fallback = left_list.len().cmp(&right_list.len());
-
-    left_list
-        .iter()
-        .zip(right_list.iter())
-        .find_map(|(left, right)| match left.cmp(right) {
-            std::cmp::Ordering::Less => Some(std::cmp::Ordering::Less),
-            std::cmp::Ordering::Equal => None,
-            std::cmp::Ordering::Greater => Some(std::cmp::Ordering::Greater),
-        })
-        .unwrap_or(fallback)
+impl ListItem {
+    /// Compares two lists item by item, the shorter list is smaller if all shared items are equal
+    pub fn compare(left_list: &[ListItem], right_list: &[ListItem]) -> std::cmp::Ordering {
+        let fallback = left_list.len().cmp(&right_list.len());
+
+        left_list
+            .iter()
+            .zip(right_list.iter())
+            .find_map(|(left, right)| match left.cmp(right) {
+                std::cmp::Ordering::Less => Some(std::cmp::Ordering::Less),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(std::cmp::Ordering::Greater),
+            })
+            .unwrap_or(fallback)
+    }
 }
 
 impl Ord for ListItem {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self {
             Self::List(left_list) => match other {
-                Self::List(right_list) => compare_lists(left_list, right_list),
+                Self::List(right_list) => Self::compare(left_list, right_list),
                 Self::Int(right_int) => {
-                    compare_lists(left_list, &vec![Self::Int(*right_int)])
+                    Self::compare(left_list, &[Self::Int(*right_int)])
                 }
             },
             Self::Int(left_int) => match other {
                 Self::List(right_list) => {
-                    compare_lists(&vec![Self::Int(*left_int)], right_list)
+                    Self::compare(&[Self::Int(*left_int)], right_list)
                 }
                 Self::Int(right_int) => left_int.cmp(right_int),
             },
@@ -58,7 +61,12 @@ fn read_int<I: Iterator<Item = char>>(iter: &mut Peekable<I>) -> Option<ListItem
     s.parse().map(ListItem::Int).ok()
 }
 
+fn skip_whitespace<I: Iterator<Item = char>>(iter: &mut Peekable<I>) {
+    while iter.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
 fn read_item<I: Iterator<Item = char>>(iter: &mut Peekable<I>) -> Option<ListItem> {
+    skip_whitespace(iter);
     let peek = *iter.peek().unwrap();
     if peek == '[' {
         read_list(iter)
@@ -81,6 +89,7 @@ fn read_list<I: Iterator<Item = char>>(iter: &mut Peekable<I>) -> Option<ListIte
         if let Some(item) = read_item(iter) {
             out.push(item);
         }
+        skip_whitespace(iter);
 
         if iter.next_if_eq(&']').is_some() {
             return Some(ListItem::List(out));
@@ -115,29 +124,43 @@ fn sum_indexes(packages: &[ListItem]) -> usize {
     score
 }
 
-fn decoder_key(mut packages: Vec<ListItem>) -> i32 {
-    packages.push(ListItem::from_str("[[2]]").expect("divider 2 to parse"));
-    packages.push(ListItem::from_str("[[6]]").expect("divider 6 to parse"));
-
-    packages.sort();
-
-    let scantarget_1 = ListItem::from_str("[[2]]").expect("divider 2 to parse");
-    let scantarget_2 = ListItem::from_str("[[6]]").expect("divider 6 to parse");
+// Ranks the dividers by counting the packages that sort before them, no sorting needed
+fn decoder_key(packages: &[ListItem]) -> i32 {
+    let divider_1 = ListItem::from_str("[[2]]").expect("divider 2 to parse");
+    let divider_2 = ListItem::from_str("[[6]]").expect("divider 6 to parse");
 
-    let pos_1 = packages
-        .iter()
-        .position(|item| *item == scantarget_1)
-        .expect("To find scan target 1")
-        + 1;
-    let pos_2 = packages
-        .iter()
-        .position(|item| *item == scantarget_2)
-        .expect("to find scan target 2")
-        + 1;
+    let pos_1 = packages.iter().filter(|item| **item < divider_1).count() + 1;
+    // Divider 1 sorts before divider 2 as well
+    let pos_2 = packages.iter().filter(|item| **item < divider_2).count() + 2;
 
     (pos_1 * pos_2) as i32
 }
 
+pub(crate) const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
 // https://adventofcode.com/2022/day/13
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let lines: Result<Vec<ListItem>, _> = input
@@ -149,17 +172,31 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let lines = lines.expect("Everything to parse");
     let index_sum = sum_indexes(&lines);
 
-    Ok(DayOutput {
-        part1: Some(super::PartResult::Int(index_sum as i32)),
-        part2: Some(super::PartResult::Int(decoder_key(lines))),
-    })
+    Ok(DayOutput::two(
+        Some(super::PartResult::Int(index_sum as i32)),
+        Some(super::PartResult::Int(decoder_key(&lines))),
+    ))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{cmp::Ordering, str::FromStr};
 
-    use crate::solutions::day13::{decoder_key, sum_indexes, ListItem};
+    use crate::solutions::day13::{decoder_key, sum_indexes, ListItem, EXAMPLE};
 
     fn test_strs(left: &str, right: &str, expected_ordering: std::cmp::Ordering) {
         assert_eq!(
@@ -171,32 +208,7 @@ mod tests {
     }
 
     fn parse_example_input() -> Vec<ListItem> {
-        let input = "[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
-
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]
-";
-
-        let lines: Result<Vec<ListItem>, _> = input
+        let lines: Result<Vec<ListItem>, _> = EXAMPLE
             .lines()
             .filter(|line| !line.is_empty())
             .map(ListItem::from_str)
@@ -223,6 +235,31 @@ mod tests {
         test_strs("[2]", "[1]", Ordering::Greater);
     }
 
+    #[test]
+    fn whitespace() {
+        assert_eq!(
+            "[1, 2, 3]".parse::<ListItem>(),
+            "[1,2,3]".parse::<ListItem>()
+        );
+        assert_eq!("[ ]".parse::<ListItem>(), Ok(ListItem::List(vec![])));
+        assert_eq!(
+            " [ [1] ,\t[ 2 , [] ] ]".parse::<ListItem>(),
+            "[[1],[2,[]]]".parse::<ListItem>()
+        );
+    }
+
+    #[test]
+    fn compare_slices() {
+        let left = [ListItem::Int(1), ListItem::List(vec![ListItem::Int(2)])];
+        let right = [ListItem::Int(1), ListItem::Int(3)];
+
+        assert_eq!(ListItem::compare(&left, &right), Ordering::Less);
+        assert_eq!(ListItem::compare(&right, &left), Ordering::Greater);
+        assert_eq!(ListItem::compare(&left, &left), Ordering::Equal);
+        assert_eq!(ListItem::compare(&left[..1], &left), Ordering::Less);
+        assert_eq!(ListItem::compare(&[], &[]), Ordering::Equal);
+    }
+
     #[test]
     fn examples() {
         test_strs("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less);
@@ -251,6 +288,15 @@ mod tests {
 
     #[test]
     fn example_decoder() {
-        assert_eq!(decoder_key(parse_example_input()), 140);
+        assert_eq!(decoder_key(&parse_example_input()), 140);
+    }
+
+    #[test]
+    fn both_parts_borrow_the_same_packages() {
+        let packages = parse_example_input();
+
+        assert_eq!(decoder_key(&packages), 140);
+        assert_eq!(sum_indexes(&packages), 13);
+        assert_eq!(decoder_key(&packages), 140);
     }
 }
@@ -1,24 +1,19 @@
 use std::collections::HashSet;
 
-use crate::grid::Grid;
+use crate::grid::{Grid, WorldPos};
+use crate::util::fixpoint_capped;
 use crate::vec2d::Vec2DBounds;
 
 use crate::vec2d::{Vec2D, DOWN, LEFT, RIGHT};
 
-use super::{DayOutput, LogicError};
+use super::{DayOutput, LogicError, Solution};
 
 type VecSet = HashSet<Vec2D<i32>>;
 
 const SAND_ENTRY_POINT: Vec2D<i32> = Vec2D { x: 500, y: 0 };
 
 fn insert_line(from: &Vec2D<i32>, to: &Vec2D<i32>, set: &mut HashSet<Vec2D<i32>>) {
-    let dir = (*to - *from).normalized();
-    let mut cur = *from;
-    while cur != *to {
-        set.insert(cur);
-        cur = cur + dir;
-    }
-    set.insert(*to);
+    set.extend(from.points_between(to));
 }
 
 fn build_walls(input: &str) -> HashSet<Vec2D<i32>> {
@@ -29,7 +24,11 @@ fn build_walls(input: &str) -> HashSet<Vec2D<i32>> {
         .map(|line| {
             line.split(" -> ")
                 // .inspect(|f| println!("{:?}", f))
-                .map(|vecstr| vecstr.parse::<Vec2D<i32>>().unwrap())
+                .map(|vecstr| {
+                    vecstr
+                        .parse::<Vec2D<i32>>()
+                        .unwrap_or_else(|e| panic!("Invalid wall coordinate: {e}"))
+                })
                 .collect()
         })
         .collect();
@@ -45,25 +44,28 @@ fn build_walls(input: &str) -> HashSet<Vec2D<i32>> {
     walls
 }
 
-// Find the lowest point of the given vectors
-fn lowest_point(walls: &VecSet) -> i32 {
-    walls
-        .iter()
-        .fold(0, |acc, cur| if (cur.y) > acc { cur.y } else { acc })
+// Find the lowest point of the given vectors, None if there are none
+fn lowest_point(walls: &VecSet) -> Option<i32> {
+    walls.iter().map(|wall| wall.y).max()
 }
 
 struct SandPathIterator<'a> {
     position: Vec2D<i32>,
     cave: &'a VecSet,
     floor: Option<i32>,
+    max_y: i32, // Every step goes down by one, stopping here guarantees the iterator ends
 }
 
 impl<'a> SandPathIterator<'a> {
     fn new(position: Vec2D<i32>, cave: &'a VecSet, floor: Option<i32>) -> Self {
+        // Without a floor sand below the lowest wall falls forever, without walls it does so right away
+        let max_y = floor.unwrap_or_else(|| lowest_point(cave).map_or(position.y, |y| y + 1));
+
         Self {
             position,
             cave,
             floor,
+            max_y,
         }
     }
 }
@@ -72,7 +74,12 @@ impl<'a> Iterator for SandPathIterator<'a> {
     type Item = Vec2D<i32>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.position.y >= self.max_y {
+            return None;
+        }
+
         let next_pos = sand_next_position(self.cave, self.position, self.floor)?;
+        debug_assert_ne!(next_pos, self.position, "Sand should always move");
         self.position = next_pos;
         Some(next_pos)
     }
@@ -90,16 +97,16 @@ fn print_cave(cave: &VecSet) {
 
     // let mut grid = Grid::new(size.x.try_into().unwrap(), size.y.try_into().unwrap());
     let mut grid = Grid::new_with_content(content, size.x.try_into().unwrap()).unwrap();
-
-    grid.set(&Vec2D { x: 8, y: 0 }, 'X');
-    // println!("{}", grid);
+    let origin = WorldPos(min);
 
     // println!("size: {:?}", size);
 
     for pos in cave {
-        let gridpos = *pos - min;
+        let gridpos = grid
+            .to_grid(&origin, &WorldPos(*pos))
+            .expect("Bounds to contain every position");
         // println!("{:?}", gridpos);
-        grid.set(&gridpos, 'X');
+        grid.set_by_pos(&gridpos, 'X');
     }
 
     println!("{grid}");
@@ -139,31 +146,53 @@ fn sand_next_position(
     None
 }
 
-fn find_abbys_count(mut walls: VecSet) -> i32 {
-    let mut resting_sand_count = 0;
-    let floor = lowest_point(&walls);
-    let mut sand_pos = SAND_ENTRY_POINT;
-    loop {
-        let next_position = sand_next_position(&walls, sand_pos, None);
-
-        sand_pos = match next_position {
-            Some(pos) => pos,
-            None => {
-                resting_sand_count += 1;
-                walls.insert(sand_pos);
-                SAND_ENTRY_POINT
-            }
-        };
+fn find_abbys_count(walls: VecSet) -> Result<i32, LogicError> {
+    Ok(fill_until_abbys(walls)?.0)
+}
 
-        if sand_pos.y > floor {
-            return resting_sand_count;
-        }
-    }
+// Drop sand until it falls into the abbys, returns the resting sand count and the walls with the resting sand added
+fn fill_until_abbys(walls: VecSet) -> Result<(i32, VecSet), LogicError> {
+    let Some(floor) = lowest_point(&walls) else {
+        return Ok((0, walls));
+    };
+
+    // Row y below the source holds at most 2y+1 grains, so at most height^2 grains come to rest,
+    // each falling at most height steps before it does
+    let height = (floor - SAND_ENTRY_POINT.y).max(0) as usize + 1;
+    let max_steps = (height * height + 1) * (height + 1);
+
+    // The step keeps going while the sand is above the lowest wall, once below it falls forever
+    let (resting_sand_count, walls, _) = fixpoint_capped(
+        (0, walls, SAND_ENTRY_POINT),
+        max_steps,
+        |(resting_sand_count, walls, sand_pos)| {
+            *sand_pos = match sand_next_position(walls, *sand_pos, None) {
+                Some(pos) => pos,
+                None => {
+                    *resting_sand_count += 1;
+                    walls.insert(*sand_pos);
+                    SAND_ENTRY_POINT
+                }
+            };
+
+            sand_pos.y <= floor
+        },
+    )
+    .map_err(|e| LogicError(format!("Sand never fell into the abyss: {e}")))?;
+
+    Ok((resting_sand_count, walls))
+}
+
+fn find_blocked_source_count(walls: VecSet) -> i32 {
+    fill_until_source_blocked(walls).0
 }
 
-fn find_blocked_source_count(mut walls: VecSet) -> i32 {
+// Drop sand until the source is blocked, returns the resting sand count and the walls with the resting sand added
+fn fill_until_source_blocked(mut walls: VecSet) -> (i32, VecSet) {
     let mut resting_sand_count = 0;
-    let floor = Some(lowest_point(&walls) + 2);
+    // Walls above the source don't push the floor up past it
+    let lowest = lowest_point(&walls).map_or(SAND_ENTRY_POINT.y, |y| y.max(SAND_ENTRY_POINT.y));
+    let floor = Some(lowest + 2);
 
     let mut path = vec![SAND_ENTRY_POINT];
     path.extend(SandPathIterator::new(SAND_ENTRY_POINT, &walls, floor));
@@ -184,28 +213,50 @@ fn find_blocked_source_count(mut walls: VecSet) -> i32 {
         }
     }
 
-    resting_sand_count
+    (resting_sand_count, walls)
 }
 
+pub(crate) const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
 // https://adventofcode.com/2022/day/14
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     // let cave = build_walls(input);
-    let abbyscount = find_abbys_count(build_walls(input));
+    let abbyscount = find_abbys_count(build_walls(input))?;
     let source_block_count = find_blocked_source_count(build_walls(input));
 
-    Ok(DayOutput {
-        part1: Some(super::PartResult::Int(abbyscount)),
-        part2: Some(super::PartResult::Int(source_block_count)),
-    })
+    Ok(DayOutput::two(
+        Some(super::PartResult::Int(abbyscount)),
+        Some(super::PartResult::Int(source_block_count)),
+    ))
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use std::collections::HashSet;
+
     use crate::solutions::day14::lowest_point;
+    use crate::vec2d::{Vec2D, DOWN, LEFT, RIGHT};
 
     use super::{
-        build_walls, find_abbys_count, sand_next_position, SandPathIterator, SAND_ENTRY_POINT,
+        build_walls, fill_until_abbys, fill_until_source_blocked, find_abbys_count,
+        sand_next_position, SandPathIterator, EXAMPLE, SAND_ENTRY_POINT,
     };
 
     // use crate::solutions::day13::{decoder_key, sum_indexes, ListItem};
@@ -217,22 +268,34 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+        let input = EXAMPLE;
         let cave = build_walls(input);
 
         // println!("START CAVE");
         // print_cave(&cave);
 
-        let abbyscount = find_abbys_count(cave);
+        let abbyscount = find_abbys_count(cave).ok().unwrap();
 
         assert_eq!(abbyscount, 24);
     }
 
+    #[test]
+    fn resting_sand() {
+        let input = EXAMPLE;
+        let wall_count = build_walls(input).len();
+
+        let (count, cave) = fill_until_abbys(build_walls(input)).ok().unwrap();
+        assert_eq!(count, 24);
+        assert_eq!(cave.len(), count as usize + wall_count);
+
+        let (count, cave) = fill_until_source_blocked(build_walls(input));
+        assert_eq!(count, 93);
+        assert_eq!(cave.len(), count as usize + wall_count);
+    }
+
     #[test]
     fn iterator_equality() {
-        let input = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+        let input = EXAMPLE;
         let cave = build_walls(input);
         let floor = None;
 
@@ -256,10 +319,9 @@ mod tests {
 
     #[test]
     fn iterator_equality_with_floor() {
-        let input = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+        let input = EXAMPLE;
         let cave = build_walls(input);
-        let floor = Some(lowest_point(&cave) + 2);
+        let floor = lowest_point(&cave).map(|y| y + 2);
 
         let mut path = vec![SAND_ENTRY_POINT];
         let mut pos = SAND_ENTRY_POINT;
@@ -278,4 +340,42 @@ mod tests {
 
         assert_eq!(path, iter_path);
     }
+
+    #[test]
+    fn iterator_terminates() {
+        let walled_in = HashSet::from([
+            SAND_ENTRY_POINT + DOWN,
+            SAND_ENTRY_POINT + DOWN + LEFT,
+            SAND_ENTRY_POINT + DOWN + RIGHT,
+        ]);
+        assert_eq!(
+            SandPathIterator::new(SAND_ENTRY_POINT, &walled_in, None).count(),
+            0
+        );
+
+        // Nothing to land on and no floor, the sand stops one below the lowest wall
+        let wall = HashSet::from([Vec2D { x: 0, y: 10 }]);
+        let path: Vec<_> = SandPathIterator::new(SAND_ENTRY_POINT, &wall, None).collect();
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.last(), Some(&Vec2D { x: 500, y: 11 }));
+    }
+
+    #[test]
+    fn no_walls() {
+        let walls = HashSet::new();
+        assert_eq!(lowest_point(&walls), None);
+
+        assert_eq!(fill_until_abbys(walls.clone()).ok().unwrap().0, 0);
+        // The floor sits two below the source, leaving room for a triangle of 1 + 3 grains
+        assert_eq!(fill_until_source_blocked(walls).0, 4);
+    }
+
+    #[test]
+    fn walls_above_source() {
+        let walls = HashSet::from([Vec2D { x: 500, y: -3 }, Vec2D { x: 499, y: -5 }]);
+        assert_eq!(lowest_point(&walls), Some(-3));
+
+        assert_eq!(fill_until_abbys(walls.clone()).ok().unwrap().0, 0);
+        assert_eq!(fill_until_source_blocked(walls).0, 4);
+    }
 }
@@ -5,12 +5,12 @@ use crate::vec2d::Vec2D;
 
 use crate::{range::Ranging, rangeset::RangeSet};
 
-use super::{DayOutput, LogicError};
+use super::{DayOutput, LogicError, Solution};
 
 const SEARCH_MAX_P2: i32 = 4_000_000;
 
 #[derive(Debug)]
-struct Sensor {
+pub(crate) struct Sensor {
     position: Vec2D<i32>,
     beacon_position: Vec2D<i32>,
     radius: i32,
@@ -28,26 +28,48 @@ struct Line {
 }
 
 impl Line {
-    /// Takes two lines, 2 base apart, returns the line that runs between
-    fn create_valley(&self, other: &Self) -> Self {
+    /// Takes two lines, 2 base apart, returns the line that runs between, where both lines overlap
+    /// None if they don't overlap, there is no valley between them then
+    fn create_valley(&self, other: &Self) -> Option<Self> {
         assert_eq!(self.base + 2, other.base);
 
-        Self {
+        let offset = self.offset.max(other.offset);
+        let end = (self.offset + self.length).min(other.offset + other.length);
+
+        (end >= offset).then_some(Self {
             base: self.base + 1,
-            offset: self.offset.max(other.offset),
-            length: self.length.min(other.length),
-        }
+            offset,
+            length: end - offset,
+        })
     }
 
-    fn intersection_point(&self, other: &Self) -> Vec2D<i32> {
+    /// Whether x falls within the line, widened by one on both ends
+    /// A valley runs just outside a sensor's edge, which reaches one further than the edge itself
+    fn covers_x(&self, x: i32) -> bool {
+        x >= self.offset - 1 && x <= self.offset + self.length
+    }
+
+    /// Intersection of an up line (self) and a down line, None if it falls outside either segment or between cells
+    fn intersection_point(&self, other: &Self) -> Option<Vec2D<i32>> {
+        if (self.base - other.base) % 2 != 0 {
+            return None;
+        }
+
         let x = (-other.base + self.base) / 2;
         let y = (self.base + other.base) / 2;
-        Vec2D { x, y }
+
+        (self.covers_x(x) && other.covers_x(x)).then_some(Vec2D { x, y })
     }
 }
 
 impl Sensor {
-    fn range_on_y_line(&self, y: i32) -> Option<Range> {
+    /// Whether p lies within the sensor's diamond, edge included
+    fn covers(&self, p: &Vec2D<i32>) -> bool {
+        self.position.distance_manhatten(p) <= self.radius
+    }
+
+    /// The x range the sensor's diamond covers on row y
+    fn covers_row(&self, y: i32) -> Option<Range> {
         let diff_y = (self.position.y - y).abs();
         let half_line_count = self.radius - diff_y;
         if half_line_count < 0 {
@@ -96,10 +118,17 @@ impl FromStr for Sensor {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut char_iter = s.chars();
-        let pos_x = consume_number_from_char_iter(&mut char_iter);
-        let pos_y = consume_number_from_char_iter(&mut char_iter);
-        let sensor_x = consume_number_from_char_iter(&mut char_iter);
-        let sensor_y = consume_number_from_char_iter(&mut char_iter);
+        let mut next_number = || {
+            consume_number_from_char_iter(&mut char_iter)
+                .ok_or_else(|| format!("Expected 4 coordinates in sensor line '{s}'"))
+        };
+        let pos_x = next_number()?;
+        let pos_y = next_number()?;
+        let sensor_x = next_number()?;
+        let sensor_y = next_number()?;
+        if next_number().is_ok() {
+            return Err(format!("Expected 4 coordinates in sensor line '{s}'"));
+        }
 
         let position = Vec2D { x: pos_x, y: pos_y };
         let beacon_position = Vec2D {
@@ -135,7 +164,7 @@ fn line_overlap_count(sensors: &[Sensor], y: i32) -> i32 {
 
     sensors
         .iter()
-        .filter_map(|s| s.range_on_y_line(y))
+        .filter_map(|s| s.covers_row(y))
         .for_each(|r| {
             set.insert((r.lower, r.upper + 1));
         });
@@ -151,17 +180,15 @@ fn line_overlap_count(sensors: &[Sensor], y: i32) -> i32 {
     overlap_count - beacons_in_range as i32
 }
 
-fn make_sensors(input: &str) -> Vec<Sensor> {
+fn make_sensors(input: &str) -> Result<Vec<Sensor>, LogicError> {
     input
         .lines()
-        .map(|s| s.parse::<Sensor>().unwrap())
+        .map(|s| s.parse::<Sensor>().map_err(LogicError))
         .collect()
 }
 
 fn is_outside_sensor_range(sensors: &[Sensor], position: &Vec2D<i32>) -> bool {
-    sensors
-        .iter()
-        .all(|sensor| sensor.position.distance_manhatten(position) > sensor.radius)
+    sensors.iter().all(|sensor| !sensor.covers(position))
 }
 
 fn find_empty_spot(sensors: &[Sensor], max: i32) -> u64 {
@@ -181,21 +208,21 @@ fn find_empty_spot(sensors: &[Sensor], max: i32) -> u64 {
 
     let up_line_valleys: Vec<Line> = up_lines
         .iter()
-        .filter_map(|line| {
+        .flat_map(|line| {
             up_lines
                 .iter()
-                .find(|other_line| line.base + 2 == other_line.base)
-                .map(|other_line| line.create_valley(other_line))
+                .filter(|other_line| line.base + 2 == other_line.base)
+                .filter_map(|other_line| line.create_valley(other_line))
         })
         .collect();
 
     let down_line_valleys: Vec<Line> = down_lines
         .iter()
-        .filter_map(|line| {
+        .flat_map(|line| {
             down_lines
                 .iter()
-                .find(|other_line| line.base + 2 == other_line.base)
-                .map(|other_line| line.create_valley(other_line))
+                .filter(|other_line| line.base + 2 == other_line.base)
+                .filter_map(|other_line| line.create_valley(other_line))
         })
         .collect();
 
@@ -204,12 +231,9 @@ fn find_empty_spot(sensors: &[Sensor], max: i32) -> u64 {
         .iter()
         .find_map(|up_line| {
             down_line_valleys.iter().find_map(|down_line| {
-                let position = up_line.intersection_point(down_line);
-                if is_in_range(&position) && is_outside_sensor_range(sensors, &position) {
-                    Some(position)
-                } else {
-                    None
-                }
+                up_line.intersection_point(down_line).filter(|position| {
+                    is_in_range(position) && is_outside_sensor_range(sensors, position)
+                })
             })
         })
         .expect("Intersection should be found");
@@ -220,20 +244,57 @@ fn find_empty_spot(sensors: &[Sensor], max: i32) -> u64 {
     (intersection.x as u64) * 4_000_000 + intersection.y as u64
 }
 
-// https://adventofcode.com/2022/day/15
-pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let sensors = make_sensors(input);
+pub(crate) const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
 
-    Ok(DayOutput {
-        part1: Some(super::PartResult::Int(line_overlap_count(
-            &sensors, 2_000_000,
+// Parsing is split from solving so both can be timed separately
+pub(crate) fn parse(input: &str) -> Result<Vec<Sensor>, LogicError> {
+    make_sensors(input)
+}
+
+pub(crate) fn solve_parsed(sensors: &[Sensor]) -> DayOutput {
+    DayOutput::two(
+        Some(super::PartResult::Int(line_overlap_count(
+            sensors, 2_000_000,
         ))),
-        // part2: None,
-        part2: Some(super::PartResult::UInt(find_empty_spot(
-            &sensors,
+        // None,
+        Some(super::PartResult::UInt(find_empty_spot(
+            sensors,
             SEARCH_MAX_P2,
         ))),
-    })
+    )
+}
+
+// https://adventofcode.com/2022/day/15
+pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
+    Ok(solve_parsed(&parse(input)?))
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
@@ -246,7 +307,7 @@ mod tests {
         vec2d::Vec2D,
     };
 
-    use super::{make_sensors, Sensor};
+    use super::{make_sensors, parse, solve_parsed, Line, Sensor, EXAMPLE};
 
     #[test]
     // #[ignore = "wip"]
@@ -264,45 +325,46 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-
-        let sensors = make_sensors(input);
+        let sensors = make_sensors(EXAMPLE).ok().unwrap();
 
         assert_eq!(line_overlap_count(&sensors, 10), 26);
     }
 
     #[test]
     fn example_p2() {
+        let sensors = make_sensors(EXAMPLE).ok().unwrap();
+        assert_eq!(find_empty_spot(&sensors, SEARCH_MAX_P1), 56_000_011);
+    }
+
+    #[test]
+    fn truncated_sensor_line() {
         let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+Sensor at x=9, y=16: closest beacon is at x=10
+";
+        let err = make_sensors(input).unwrap_err();
+        assert_eq!(
+            err.0,
+            "Expected 4 coordinates in sensor line 'Sensor at x=9, y=16: closest beacon is at x=10'"
+        );
+    }
 
-        let sensors = make_sensors(input);
-        assert_eq!(find_empty_spot(&sensors, SEARCH_MAX_P1), 56_000_011);
+    #[test]
+    fn covers() {
+        let sensor = test_sensor(5, 5, 2);
+
+        // Inside
+        assert!(sensor.covers(&Vec2D { x: 5, y: 5 }));
+        assert!(sensor.covers(&Vec2D { x: 6, y: 4 }));
+
+        // On the edge
+        assert!(sensor.covers(&Vec2D { x: 7, y: 5 }));
+        assert!(sensor.covers(&Vec2D { x: 4, y: 6 }));
+        assert!(sensor.covers(&Vec2D { x: 5, y: 3 }));
+
+        // Just outside
+        assert!(!sensor.covers(&Vec2D { x: 8, y: 5 }));
+        assert!(!sensor.covers(&Vec2D { x: 6, y: 3 }));
+        assert!(!sensor.covers(&Vec2D { x: 3, y: 7 }));
     }
 
     #[test]
@@ -333,6 +395,79 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
         assert_eq!(topleft.offset, 3);
     }
 
+    #[test]
+    fn intersection_within_segments() {
+        let up = Line {
+            base: 10,
+            offset: 4,
+            length: 3,
+        };
+        let down = Line {
+            base: 0,
+            offset: 5,
+            length: 1,
+        };
+        assert_eq!(up.intersection_point(&down), Some(Vec2D { x: 5, y: 5 }));
+
+        // The lines would cross at (5,5), but neither segment reaches that far
+        let short_up = Line {
+            base: 10,
+            offset: 0,
+            length: 2,
+        };
+        let short_down = Line {
+            base: 0,
+            offset: 0,
+            length: 2,
+        };
+        assert_eq!(short_up.intersection_point(&short_down), None);
+        assert_eq!(short_up.intersection_point(&down), None);
+
+        // Crossing between cells
+        let odd_up = Line {
+            base: 11,
+            offset: 0,
+            length: 20,
+        };
+        assert_eq!(odd_up.intersection_point(&down), None);
+    }
+
+    #[test]
+    fn valley_without_overlap() {
+        let up = Line {
+            base: 0,
+            offset: 0,
+            length: 4,
+        };
+        let overlapping = Line {
+            base: 2,
+            offset: 2,
+            length: 4,
+        };
+        let valley = up.create_valley(&overlapping).unwrap();
+        assert_eq!((valley.base, valley.offset, valley.length), (1, 2, 2));
+
+        // Side by side, nothing runs between them, not even just past either end
+        let apart = Line {
+            base: 2,
+            offset: 5,
+            length: 4,
+        };
+        assert!(up.create_valley(&apart).is_none());
+
+        // An empty valley one past the end of up would otherwise still be crossed here, at (4,-3)
+        let down = Line {
+            base: -7,
+            offset: 0,
+            length: 20,
+        };
+        assert_eq!(
+            up.create_valley(&apart)
+                .and_then(|valley| valley.intersection_point(&down)),
+            None
+        );
+    }
+
     #[test]
     fn lines_down() {
         let [topright, bottomleft] = test_sensor(5, 5, 2).lines_down();
@@ -344,4 +479,23 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
         assert_eq!(bottomleft.length, 3);
         assert_eq!(bottomleft.offset, 3);
     }
+
+    #[test]
+    fn parse_example() {
+        let sensors = parse(EXAMPLE).ok().unwrap();
+
+        assert_eq!(sensors.len(), 14);
+        assert_eq!(sensors[0].position, Vec2D { x: 2, y: 18 });
+        assert_eq!(sensors[0].beacon_position, Vec2D { x: -2, y: 15 });
+        assert_eq!(sensors[0].radius, 7);
+    }
+
+    #[test]
+    fn solve_matches_parse_then_solve_parsed() {
+        let parsed = parse(EXAMPLE).ok().unwrap();
+        super::super::tests::assert_day_output_eq(
+            &super::solve(EXAMPLE).ok().unwrap(),
+            &solve_parsed(&parsed),
+        );
+    }
 }
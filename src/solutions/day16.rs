@@ -2,13 +2,15 @@ use core::panic;
 use std::{
     collections::HashMap,
     fmt::{Display, Write},
+    rc::Rc,
     str::FromStr,
+    time::{Duration, Instant},
     vec,
 };
 
 use crate::parsing::consume_when;
 
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 static START_CAVE: CaveName = CaveName('A', 'A');
 
@@ -39,11 +41,14 @@ impl From<CaveId> for usize {
     }
 }
 
-struct CaveSystem {
+pub(crate) struct CaveSystem {
     caves: Vec<Cave>,
     caves_with_working_valve: Vec<CaveId>,
 }
 
+/// Expands the breadth first search by one round, recording the round each cave was first reached in
+/// A cave can show up in the frontier again a round after it was reached, when a cave reached in the same round
+/// was expanded before it. Only the first round is the shortest distance, so later rounds must not overwrite it
 fn explore_round(
     caves: &[Cave],
     closed_set: &mut HashMap<CaveId, u32>,
@@ -54,7 +59,6 @@ fn explore_round(
 
     for cave_id in frontier {
         closed_set.entry(cave_id).or_insert(round);
-        // closed_set.insert(cave_id, round);
 
         let cave = caves.iter().find(|c| c.id == cave_id).unwrap();
 
@@ -126,7 +130,11 @@ impl CaveSystem {
     }
 
     fn connect_protocaves(protocaves: &[CavePrototype]) -> Vec<Cave> {
-        let mut name_to_id_map: HashMap<CaveName, CaveId> = HashMap::new();
+        let name_to_id_map: HashMap<CaveName, CaveId> = protocaves
+            .iter()
+            .enumerate()
+            .map(|(pos, cave)| (cave.name, CaveId(pos)))
+            .collect();
 
         let mut caves: Vec<Cave> = protocaves
             .iter()
@@ -136,23 +144,14 @@ impl CaveSystem {
                 name: cave.name,
                 flow_rate: cave.flow_rate,
                 paths: vec![],
-                tunnels: vec![],
-                tunnels_by_name: cave.tunnels.clone(),
+                tunnels: cave
+                    .tunnels
+                    .iter()
+                    .map(|name| *name_to_id_map.get(name).unwrap())
+                    .collect(),
             })
             .collect();
 
-        for cave in &caves {
-            name_to_id_map.insert(cave.name, cave.id);
-        }
-
-        caves.iter_mut().for_each(|cave| {
-            cave.tunnels.extend(
-                cave.tunnels_by_name
-                    .iter()
-                    .map(|name| *name_to_id_map.get(name).unwrap()),
-            )
-        });
-
         for origin_id in 0..caves.len() {
             calc_distances(&mut caves, origin_id)
         }
@@ -175,7 +174,6 @@ struct Cave {
     flow_rate: u32,
     paths: Vec<u32>,      // Length of paths to other caves
     tunnels: Vec<CaveId>, // Direct neighbours
-    tunnels_by_name: Vec<CaveName>,
 }
 
 impl Display for Cave {
@@ -227,9 +225,10 @@ impl World {
             .filter(|cave| !self.is_valve_open(**cave))
     }
 
-    fn open_valve(&mut self, id: CaveId, rate: u32) -> bool {
+    /// Opens the given valve, returns false if it was already open
+    fn try_open_valve(&mut self, id: CaveId, rate: u32) -> bool {
         if self.is_valve_open(id) {
-            return true;
+            return false;
         }
         let valve: u64 = 1 << id.0;
 
@@ -237,7 +236,7 @@ impl World {
         self.valves_opened |= valve;
         self.valves_opened_count += 1;
 
-        false
+        true
     }
 
     fn advance_time(&mut self, duration: u32) {
@@ -262,6 +261,28 @@ struct Path {
     world: World,
     me: Traveler,
     elephant: Traveler,
+    history: Option<Rc<OpenedValve>>, // The last valve opened, None before any were
+}
+
+/// A valve opened along a path, linking back to the one opened before it
+/// Paths branching off share their common history instead of each cloning it
+#[derive(Debug)]
+struct OpenedValve {
+    id: CaveId,
+    minute: u32,
+    previous: Option<Rc<OpenedValve>>,
+}
+
+/// The valves opened along a path and the minute they were opened, oldest first
+fn unwind_history(last: &Option<Rc<OpenedValve>>) -> Vec<(CaveId, u32)> {
+    let mut history = vec![];
+    let mut step = last.as_deref();
+    while let Some(valve) = step {
+        history.push((valve.id, valve.minute));
+        step = valve.previous.as_deref();
+    }
+    history.reverse();
+    history
 }
 
 #[derive(Clone, Debug)]
@@ -277,6 +298,15 @@ enum Goal {
     None,
 }
 
+impl Goal {
+    fn target(&self) -> Option<CaveId> {
+        match self {
+            Self::MoveTo(id, _, _) => Some(*id),
+            Self::Idle | Self::None => None,
+        }
+    }
+}
+
 impl Traveler {
     fn is_action_time(&self, time: u32) -> bool {
         match self.goal {
@@ -288,6 +318,43 @@ impl Traveler {
 }
 
 impl Path {
+    /// The total relieved pressure if this path has run out of time, None if it can still continue
+    fn final_pressure(&self, max_cave_time: u32) -> Option<u32> {
+        (self.world.minutes == max_cave_time).then(|| self.world.pressure_at_time(max_cave_time))
+    }
+
+    /// An optimistic estimate of the pressure this path can still reach, never lower than the real outcome
+    /// Valves being walked to open when planned, every other closed valve opens 2 minutes from now,
+    /// the least time needed to walk to any other cave and open it
+    fn upper_bound(&self, cave_system: &CaveSystem, max_cave_time: u32) -> u32 {
+        let open_at = |time: u32, rate: u32| rate * max_cave_time.saturating_sub(time);
+
+        let planned: u32 = [&self.me.goal, &self.elephant.goal]
+            .into_iter()
+            .map(|goal| match goal {
+                Goal::MoveTo(_, time, rate) => open_at(*time, *rate),
+                Goal::Idle | Goal::None => 0,
+            })
+            .sum();
+
+        if self.me.goal == Goal::Idle && self.elephant.goal == Goal::Idle {
+            return self.world.pressure_at_time(max_cave_time) + planned;
+        }
+
+        let unplanned: u32 = self
+            .world
+            .closed_valves(cave_system)
+            .filter(|id| Some(**id) != self.me.goal.target())
+            .filter(|id| Some(**id) != self.elephant.goal.target())
+            .map(|id| open_at(self.world.minutes + 2, cave_system.caves[id.0].flow_rate))
+            .sum();
+
+        self.world.pressure_at_time(max_cave_time) + planned + unplanned
+    }
+
+    /// Pushes every path that can follow from this one onto the queue
+    /// left_options and right_options are scratch buffers shared between calls to avoid allocations,
+    /// they are cleared on entry and only read after both have been filled within the same call
     fn futures(
         &mut self,
         cave_system: &CaveSystem,
@@ -295,98 +362,103 @@ impl Path {
         max_cave_time: u32,
         left_options: &mut Vec<Goal>,
         right_options: &mut Vec<Goal>,
-        max: &mut u32,
     ) {
-        let time = self.world.minutes;
-        if time == max_cave_time {
-            let res = self.world.pressure_at_time(max_cave_time);
-            if res > *max {
-                *max = res;
-            }
-            return;
-        }
+        left_options.clear();
+        right_options.clear();
 
-        if time > max_cave_time || (self.me.goal == Goal::Idle && self.elephant.goal == Goal::Idle)
+        let time = self.world.minutes;
+        if time >= max_cave_time || (self.me.goal == Goal::Idle && self.elephant.goal == Goal::Idle)
         {
             return;
         }
 
-        left_options.clear();
-        right_options.clear();
-
         if self.me.is_action_time(time) {
-            let abort = match &self.me.goal {
+            match &self.me.goal {
                 Goal::MoveTo(id, _, rate) => {
                     self.me.position = *id;
-                    self.world.open_valve(*id, *rate)
+                    if !self.world.try_open_valve(*id, *rate) {
+                        return;
+                    }
+                    self.history = Some(Rc::new(OpenedValve {
+                        id: *id,
+                        minute: time,
+                        previous: self.history.take(),
+                    }));
                 }
                 Goal::Idle => panic!("Unepexted idle hit2"),
-                Goal::None => false,
-            };
-            if abort {
-                return;
-            } else {
-                let me_cave = cave_system.caves.get(self.me.position.0).unwrap();
-                left_options.push(Goal::Idle);
-                left_options.extend(
-                    self.world
-                        .closed_valves(cave_system)
-                        .filter(|cave| {
-                            let effect_time = me_cave.paths.get(cave.0).unwrap() + 1;
-                            self.world.minutes + effect_time < max_cave_time
-                        })
-                        .map(|cave| {
-                            let effect_time = me_cave.paths.get(cave.0).unwrap() + 1;
-                            let rate = cave_system.caves.get(cave.0).unwrap().flow_rate;
-                            Goal::MoveTo(*cave, self.world.minutes + effect_time, rate)
-                        }),
-                );
+                Goal::None => (),
             }
+
+            let me_cave = cave_system.caves.get(self.me.position.0).unwrap();
+            left_options.push(Goal::Idle);
+            left_options.extend(
+                self.world
+                    .closed_valves(cave_system)
+                    .filter(|cave| {
+                        let effect_time = me_cave.paths.get(cave.0).unwrap() + 1;
+                        self.world.minutes + effect_time < max_cave_time
+                    })
+                    .map(|cave| {
+                        let effect_time = me_cave.paths.get(cave.0).unwrap() + 1;
+                        let rate = cave_system.caves.get(cave.0).unwrap().flow_rate;
+                        Goal::MoveTo(*cave, self.world.minutes + effect_time, rate)
+                    }),
+            );
         } else {
             left_options.push(self.me.goal.clone());
         }
 
         if self.elephant.is_action_time(time) {
-            let abort = match &self.elephant.goal {
+            match &self.elephant.goal {
                 Goal::MoveTo(id, _, rate) => {
                     self.elephant.position = *id;
-                    self.world.open_valve(*id, *rate)
+                    if !self.world.try_open_valve(*id, *rate) {
+                        return;
+                    }
+                    self.history = Some(Rc::new(OpenedValve {
+                        id: *id,
+                        minute: time,
+                        previous: self.history.take(),
+                    }));
                 }
                 Goal::Idle => panic!("Unepexted idle hit2"),
-                Goal::None => false,
-            };
-            if abort {
-                return;
-            } else {
-                let ele_cave = cave_system.caves.get(self.elephant.position.0).unwrap();
-                right_options.push(Goal::Idle);
-                right_options.extend(
-                    self.world
-                        .closed_valves(cave_system)
-                        .filter(|cave| {
-                            let effect_time = ele_cave.paths.get(cave.0).unwrap() + 1;
-                            self.world.minutes + effect_time < max_cave_time
-                        })
-                        .map(|cave| {
-                            let effect_time = ele_cave.paths.get(cave.0).unwrap() + 1;
-                            let rate = cave_system.caves.get(cave.0).unwrap().flow_rate;
-                            Goal::MoveTo(*cave, self.world.minutes + effect_time, rate)
-                        }),
-                );
+                Goal::None => (),
             }
 
+            let ele_cave = cave_system.caves.get(self.elephant.position.0).unwrap();
+            right_options.push(Goal::Idle);
+            right_options.extend(
+                self.world
+                    .closed_valves(cave_system)
+                    .filter(|cave| {
+                        let effect_time = ele_cave.paths.get(cave.0).unwrap() + 1;
+                        self.world.minutes + effect_time < max_cave_time
+                    })
+                    .map(|cave| {
+                        let effect_time = ele_cave.paths.get(cave.0).unwrap() + 1;
+                        let rate = cave_system.caves.get(cave.0).unwrap().flow_rate;
+                        Goal::MoveTo(*cave, self.world.minutes + effect_time, rate)
+                    }),
+            );
+
             // return self.world.pressure_at_time(max_cave_time);
         } else {
             right_options.push(self.elephant.goal.clone());
         }
 
         left_options.iter().for_each(|left_option| {
-            right_options.iter().for_each(|right_option| {
-                let mut p = self.clone();
-                p.me.goal = left_option.clone();
-                p.elephant.goal = right_option.clone();
-                queue.push(p);
-            });
+            right_options
+                .iter()
+                // Never send both travelers to open the same valve
+                .filter(|right_option| {
+                    left_option.target().is_none() || left_option.target() != right_option.target()
+                })
+                .for_each(|right_option| {
+                    let mut p = self.clone();
+                    p.me.goal = left_option.clone();
+                    p.elephant.goal = right_option.clone();
+                    queue.push(p);
+                });
         });
     }
 
@@ -445,59 +517,65 @@ impl FromStr for CavePrototype {
 }
 
 fn find_biggest_release(cave_system: &CaveSystem) -> u32 {
-    let start_cave_id = cave_system
-        .cave_by_name(START_CAVE)
-        .expect("start cave should be present in cave_system");
-
-    let initial_path = Path {
-        // history: vec![],
-        // debug: 0,
-        world: World::new(),
-        me: Traveler {
-            position: start_cave_id,
-            goal: Goal::None,
-        },
-        elephant: Traveler {
-            position: start_cave_id,
-            goal: Goal::Idle,
-        },
-    };
+    find_best_valve_order(cave_system).0
+}
 
-    let mut queue = vec![initial_path];
+/// The biggest release without the elephant, along with the valves opened to achieve it and the minute each was opened
+fn find_best_valve_order(cave_system: &CaveSystem) -> (u32, Vec<(CaveName, u32)>) {
+    let (biggest_release, best_history) = search(cave_system, 30, false);
 
-    let mut biggest_release: u32 = 0;
-    // let mut iter = 0;
+    let order = best_history
+        .into_iter()
+        .map(|(id, time)| (cave_system.caves[id.0].name, time))
+        .collect();
 
-    let mut left = vec![];
-    let mut right = vec![];
+    (biggest_release, order)
+}
 
-    while let Some(mut path) = queue.pop() {
-        // path.world.advance_time_to(path.next_action_time());
-        // biggest_release = biggest_release.max(path.world.pressure_at_time(30));
-        path.world.advance_time_to(path.next_action_time(30));
-        // biggest_release = pressure.max(biggest_release);
+fn find_biggest_release_with_elephant(cave_system: &CaveSystem) -> u32 {
+    search(cave_system, 26, true).0
+}
 
-        path.futures(
-            cave_system,
-            &mut queue,
-            30,
-            &mut left,
-            &mut right,
-            &mut biggest_release,
-        );
-    }
+/// Like find_biggest_release_with_elephant, but gives up once the budget runs out
+/// Returns the best release found so far and whether it is known to be the best possible
+fn find_biggest_release_with_elephant_within(
+    cave_system: &CaveSystem,
+    budget: Duration,
+) -> (u32, bool) {
+    let (pressure, _, optimal) = search_until(cave_system, 26, true, Some(Instant::now() + budget));
+    (pressure, optimal)
+}
 
-    biggest_release
+/// Explores every path from the start cave, returning the biggest release and the history of the path achieving it
+/// Without an elephant its traveler idles from the start and never acts
+fn search(
+    cave_system: &CaveSystem,
+    minutes: u32,
+    with_elephant: bool,
+) -> (u32, Vec<(CaveId, u32)>) {
+    let (biggest_release, best_history, _) =
+        search_until(cave_system, minutes, with_elephant, None);
+    (biggest_release, best_history)
 }
 
-fn find_biggest_release_with_elephant(cave_system: &CaveSystem) -> u32 {
+/// The search, stopped early once the deadline passes, the flag tells whether it got to finish
+/// The deadline is only honoured once a complete path was found, so there is always an answer to return
+fn search_until(
+    cave_system: &CaveSystem,
+    minutes: u32,
+    with_elephant: bool,
+    deadline: Option<Instant>,
+) -> (u32, Vec<(CaveId, u32)>, bool) {
+    // Without a valve worth opening there is nothing to release, no matter the path
+    if cave_system.caves_with_working_valve.is_empty() {
+        return (0, vec![], true);
+    }
+
     let start_cave_id = cave_system
         .cave_by_name(START_CAVE)
         .expect("start cave should be present in cave_system");
 
     let mut queue = vec![Path {
-        // history: vec![],
-        // debug: 0,
         world: World::new(),
         me: Traveler {
             position: start_cave_id,
@@ -505,99 +583,506 @@ fn find_biggest_release_with_elephant(cave_system: &CaveSystem) -> u32 {
         },
         elephant: Traveler {
             position: start_cave_id,
-            goal: Goal::None,
+            goal: if with_elephant {
+                Goal::None
+            } else {
+                Goal::Idle
+            },
         },
+        history: None,
     }];
 
+    let mut biggest_release: u32 = 0;
+    let mut best_history = None;
+    let mut found_complete_path = false;
+    let mut explored: u32 = 0;
+
     let mut left = vec![];
     let mut right = vec![];
 
-    let mut biggest_release: u32 = 0;
-
     while let Some(mut path) = queue.pop() {
-        // path.resolve_actions(cave_system, 26);
-        // biggest_release = biggest_release.max(path.world.pressure_at_time(26));
-        path.world.advance_time_to(path.next_action_time(26));
-        path.futures(
-            cave_system,
-            &mut queue,
-            26,
-            &mut left,
-            &mut right,
-            &mut biggest_release,
-        );
+        // Reading the clock on every path would slow the search down noticeably
+        explored = explored.wrapping_add(1);
+        if found_complete_path
+            && explored.is_multiple_of(1024)
+            && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return (biggest_release, unwind_history(&best_history), false);
+        }
+
+        path.world.advance_time_to(path.next_action_time(minutes));
+
+        if let Some(pressure) = path.final_pressure(minutes) {
+            found_complete_path = true;
+            if pressure > biggest_release {
+                biggest_release = pressure;
+                best_history = path.history;
+            }
+            continue;
+        }
+
+        // Nothing down this branch can beat what we already found
+        if path.upper_bound(cave_system, minutes) <= biggest_release {
+            continue;
+        }
+
+        path.futures(cave_system, &mut queue, minutes, &mut left, &mut right);
     }
 
-    biggest_release
+    (biggest_release, unwind_history(&best_history), true)
 }
 
-// https://adventofcode.com/2022/day/16
-pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
+pub(crate) const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+// Parsing is split from solving so both can be timed separately
+// Distances between all caves are computed once here, both parts search the same cave system
+pub(crate) fn parse(input: &str) -> Result<CaveSystem, LogicError> {
     let caves = CaveSystem::from_str(input);
+    if caves.cave_by_name(START_CAVE).is_none() {
+        return Err(LogicError(format!("no start valve {START_CAVE}")));
+    }
 
-    // println!("{}", caves);
-    let pressure = find_biggest_release(&caves);
-    // let p2 = find_biggest_release_with_elephant(&caves);
+    Ok(caves)
+}
 
-    // println!("{},{}", pressure, p2);
-    // let p2 = 0;
+pub(crate) fn solve_parsed(caves: &CaveSystem) -> DayOutput {
+    let pressure = find_biggest_release(caves);
+    let pressure_with_elephant = find_biggest_release_with_elephant(caves);
 
-    if true {
-        Ok(DayOutput {
-            part1: Some(PartResult::UInt(pressure as u64)),
-            part2: Some(PartResult::Str("it slow".to_owned())),
-        })
+    DayOutput::two(
+        Some(PartResult::UInt(u64::from(pressure))),
+        Some(PartResult::UInt(u64::from(pressure_with_elephant))),
+    )
+}
+
+/// Like solve_parsed, but settles for the best release with the elephant found once the budget runs out
+fn solve_parsed_within(caves: &CaveSystem, budget: Duration) -> DayOutput {
+    let start = Instant::now();
+    let pressure = find_biggest_release(caves);
+    let (pressure_with_elephant, optimal) =
+        find_biggest_release_with_elephant_within(caves, budget.saturating_sub(start.elapsed()));
+
+    DayOutput::two(
+        Some(PartResult::UInt(u64::from(pressure))),
+        Some(anytime_result(pressure_with_elephant, optimal)),
+    )
+}
+
+// An answer that may not be the best possible is marked, so it's never mistaken for the real one
+fn anytime_result(pressure: u32, optimal: bool) -> PartResult {
+    if optimal {
+        PartResult::UInt(u64::from(pressure))
     } else {
-        Ok(DayOutput {
-            part1: Some(PartResult::UInt(pressure as u64)),
-            part2: Some(PartResult::UInt(0)),
-        })
+        PartResult::Str(format!("{pressure} (not proven optimal)"))
+    }
+}
+
+// https://adventofcode.com/2022/day/16
+pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
+    let caves = parse(input)?;
+
+    Ok(match super::search_budget() {
+        Some(budget) => solve_parsed_within(&caves, budget),
+        None => solve_parsed(&caves),
+    })
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
 
     use crate::solutions::day16::CaveSystem;
 
-    use super::{find_biggest_release, find_biggest_release_with_elephant, START_CAVE};
-
-    static EXAMPLE_INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
+    use super::{
+        anytime_result, find_best_valve_order, find_biggest_release,
+        find_biggest_release_with_elephant, find_biggest_release_with_elephant_within, parse,
+        solve_parsed, solve_parsed_within, CaveId, CaveName, Goal, PartResult, Path, Traveler,
+        World, EXAMPLE, START_CAVE,
+    };
 
     #[test]
-    #[ignore = "performance"]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(16, super::solve)
     }
 
     #[test]
     fn example() {
-        let caves = CaveSystem::from_str(EXAMPLE_INPUT);
+        let caves = CaveSystem::from_str(EXAMPLE);
         let pressure = find_biggest_release(&caves);
 
         assert_eq!(pressure, 1651);
     }
 
+    #[test]
+    fn elephant_within_budget() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+
+        // Out of time straight away, still the first complete path is returned
+        let (pressure, _) = find_biggest_release_with_elephant_within(&caves, Duration::ZERO);
+        assert!(pressure > 0 && pressure <= 1707);
+
+        assert_eq!(
+            find_biggest_release_with_elephant_within(&caves, Duration::from_secs(60)),
+            (1707, true)
+        );
+    }
+
+    #[test]
+    fn solve_within_budget() {
+        let caves = parse(EXAMPLE).ok().unwrap();
+
+        super::super::tests::assert_day_output_eq(
+            &solve_parsed(&caves),
+            &solve_parsed_within(&caves, Duration::from_secs(60)),
+        );
+
+        assert_eq!(anytime_result(1707, true), PartResult::UInt(1707));
+        assert_eq!(
+            anytime_result(1600, false),
+            PartResult::Str("1600 (not proven optimal)".to_owned())
+        );
+    }
+
+    #[test]
+    fn missing_start_cave() {
+        let input = "Valve BB has flow rate=5; tunnel leads to valve CC
+Valve CC has flow rate=7; tunnel leads to valve BB";
+
+        let err = super::solve(input).err().unwrap();
+        assert_eq!(err.0, "no start valve AA");
+    }
+
+    #[test]
+    fn no_working_valves() {
+        let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC
+Valve BB has flow rate=0; tunnel leads to valve AA
+Valve CC has flow rate=0; tunnel leads to valve AA";
+
+        let caves = super::parse(input).ok().unwrap();
+        assert_eq!(super::find_biggest_release(&caves), 0);
+        assert_eq!(super::find_biggest_release_with_elephant(&caves), 0);
+        assert!(super::find_best_valve_order(&caves).1.is_empty());
+    }
+
+    // Exhaustively finds the best pressure reachable from the path, checking the bound on every step along the way
+    fn best_from_checking_bound(cave_system: &CaveSystem, mut path: Path, minutes: u32) -> u32 {
+        path.world.advance_time_to(path.next_action_time(minutes));
+
+        if let Some(pressure) = path.final_pressure(minutes) {
+            return pressure;
+        }
+
+        let bound = path.upper_bound(cave_system, minutes);
+
+        let mut children = vec![];
+        path.futures(
+            cave_system,
+            &mut children,
+            minutes,
+            &mut vec![],
+            &mut vec![],
+        );
+
+        let best = children
+            .into_iter()
+            .map(|child| best_from_checking_bound(cave_system, child, minutes))
+            .max()
+            .unwrap_or(0);
+
+        assert!(
+            bound >= best,
+            "Bound {bound} below reachable {best} for {path:?}"
+        );
+        best
+    }
+
+    #[test]
+    fn upper_bound_is_admissible() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+        let start = caves.cave_by_name(START_CAVE).unwrap();
+        let traveler = |goal| Traveler {
+            position: start,
+            goal,
+        };
+
+        let solo = Path {
+            world: World::new(),
+            me: traveler(Goal::None),
+            elephant: traveler(Goal::Idle),
+            history: None,
+        };
+        assert_eq!(best_from_checking_bound(&caves, solo, 30), 1651);
+
+        let duo = Path {
+            world: World::new(),
+            me: traveler(Goal::None),
+            elephant: traveler(Goal::None),
+            history: None,
+        };
+        assert_eq!(best_from_checking_bound(&caves, duo, 26), 1707);
+    }
+
+    #[test]
+    fn cave_system_is_built_once_and_shared() {
+        let caves = parse(EXAMPLE).ok().unwrap();
+        let rebuilt = parse(EXAMPLE).ok().unwrap();
+
+        assert_eq!(
+            caves.caves_with_working_valve,
+            rebuilt.caves_with_working_valve
+        );
+        assert!(caves
+            .caves
+            .iter()
+            .zip(&rebuilt.caves)
+            .all(|(a, b)| a.name == b.name && a.paths == b.paths));
+
+        let shared = &caves;
+        assert_eq!(find_biggest_release(shared), 1651);
+        assert_eq!(find_biggest_release_with_elephant(shared), 1707);
+        assert_eq!(find_biggest_release(shared), 1651);
+    }
+
+    #[test]
+    fn example_valve_order() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+        let (pressure, order) = find_best_valve_order(&caves);
+
+        assert_eq!(pressure, 1651);
+        assert_eq!(
+            order,
+            vec![
+                (CaveName('D', 'D'), 2),
+                (CaveName('B', 'B'), 5),
+                (CaveName('J', 'J'), 9),
+                (CaveName('H', 'H'), 17),
+                (CaveName('E', 'E'), 21),
+                (CaveName('C', 'C'), 24),
+            ]
+        );
+    }
+
     #[test]
     fn example_p2() {
-        let caves = CaveSystem::from_str(EXAMPLE_INPUT);
+        let caves = CaveSystem::from_str(EXAMPLE);
         let pressure = find_biggest_release_with_elephant(&caves);
 
         assert_eq!(pressure, 1707)
     }
 
+    #[test]
+    fn travelers_never_share_a_target() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+        let start_cave_id = caves.cave_by_name(START_CAVE).unwrap();
+
+        let mut queue = vec![Path {
+            world: World::new(),
+            me: Traveler {
+                position: start_cave_id,
+                goal: Goal::None,
+            },
+            elephant: Traveler {
+                position: start_cave_id,
+                goal: Goal::None,
+            },
+            history: None,
+        }];
+
+        let mut left = vec![];
+        let mut right = vec![];
+
+        for _ in 0..5000 {
+            let Some(mut path) = queue.pop() else {
+                break;
+            };
+
+            let queue_len = queue.len();
+            path.world.advance_time_to(path.next_action_time(26));
+            path.futures(&caves, &mut queue, 26, &mut left, &mut right);
+
+            for new_path in &queue[queue_len..] {
+                let target = new_path.me.goal.target();
+                assert!(target.is_none() || target != new_path.elephant.goal.target());
+            }
+        }
+    }
+
+    #[test]
+    fn futures_option_buffers_are_not_shared() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+        let start_cave_id = caves.cave_by_name(START_CAVE).unwrap();
+        let bb = caves.cave_by_name(('B', 'B').into()).unwrap();
+        let dd = caves.cave_by_name(('D', 'D').into()).unwrap();
+
+        let fresh = Path {
+            world: World::new(),
+            me: Traveler {
+                position: start_cave_id,
+                goal: Goal::None,
+            },
+            elephant: Traveler {
+                position: start_cave_id,
+                goal: Goal::None,
+            },
+            history: None,
+        };
+
+        // Returns early after filling the left options, as BB is already open
+        let mut reopen = fresh.clone();
+        reopen.world.try_open_valve(bb, 13);
+        reopen.me.goal = Goal::MoveTo(bb, 0, 13);
+
+        // Returns early before touching the options
+        let mut out_of_time = fresh.clone();
+        out_of_time.world.advance_time_to(26);
+
+        let mut halfway = fresh.clone();
+        halfway.me.goal = Goal::MoveTo(dd, 0, 20);
+        halfway.elephant.goal = Goal::Idle;
+
+        let summarize = |queue: &[Path]| -> Vec<(Goal, Goal)> {
+            queue
+                .iter()
+                .map(|p| (p.me.goal.clone(), p.elephant.goal.clone()))
+                .collect()
+        };
+
+        let mut left = vec![];
+        let mut right = vec![];
+        for path in [&fresh, &reopen, &halfway, &out_of_time, &fresh, &halfway] {
+            let mut shared_queue = vec![];
+            path.clone()
+                .futures(&caves, &mut shared_queue, 26, &mut left, &mut right);
+
+            let mut own_queue = vec![];
+            path.clone()
+                .futures(&caves, &mut own_queue, 26, &mut vec![], &mut vec![]);
+
+            assert_eq!(summarize(&shared_queue), summarize(&own_queue));
+        }
+    }
+
+    #[test]
+    fn futures_children() {
+        let caves = CaveSystem::from_str(
+            "Valve AA has flow rate=0; tunnels lead to valves BB, CC
+Valve BB has flow rate=5; tunnel leads to valve AA
+Valve CC has flow rate=7; tunnel leads to valve AA",
+        );
+        let start_cave_id = caves.cave_by_name(START_CAVE).unwrap();
+        let bb = caves.cave_by_name(('B', 'B').into()).unwrap();
+        let cc = caves.cave_by_name(('C', 'C').into()).unwrap();
+
+        let mut path = Path {
+            world: World::new(),
+            me: Traveler {
+                position: start_cave_id,
+                goal: Goal::None,
+            },
+            elephant: Traveler {
+                position: start_cave_id,
+                goal: Goal::Idle,
+            },
+            history: None,
+        };
+
+        assert_eq!(path.final_pressure(30), None);
+
+        let mut queue = vec![];
+        path.futures(&caves, &mut queue, 30, &mut vec![], &mut vec![]);
+
+        let my_goals: Vec<Goal> = queue.iter().map(|p| p.me.goal.clone()).collect();
+        assert_eq!(
+            my_goals,
+            vec![Goal::Idle, Goal::MoveTo(bb, 2, 5), Goal::MoveTo(cc, 2, 7)]
+        );
+        assert!(queue.iter().all(|p| p.elephant.goal == Goal::Idle));
+    }
+
+    #[test]
+    fn open_valve_twice() {
+        let mut world = World::new();
+
+        assert!(world.try_open_valve(CaveId(3), 20));
+        assert!(!world.try_open_valve(CaveId(3), 20));
+        assert_eq!(world.open_valve_rate, 20);
+        assert_eq!(world.valves_opened_count, 1);
+    }
+
+    #[test]
+    fn shortest_distance_is_kept() {
+        // BB and CC are both one step from AA and each other, so CC gets queued again from BB
+        let caves = CaveSystem::from_str(
+            "Valve AA has flow rate=0; tunnels lead to valves BB, CC, DD
+Valve BB has flow rate=1; tunnels lead to valves AA, CC
+Valve CC has flow rate=1; tunnels lead to valves BB, AA, EE
+Valve DD has flow rate=1; tunnels lead to valves AA, EE
+Valve EE has flow rate=1; tunnels lead to valves CC, DD",
+        );
+        let id = |a: char, b: char| caves.cave_by_name(CaveName(a, b)).unwrap().0;
+        let distance = |from: usize, to: usize| caves.caves[from].paths[to];
+
+        assert_eq!(distance(id('A', 'A'), id('B', 'B')), 1);
+        assert_eq!(distance(id('A', 'A'), id('C', 'C')), 1);
+        assert_eq!(distance(id('A', 'A'), id('D', 'D')), 1);
+        assert_eq!(distance(id('A', 'A'), id('E', 'E')), 2);
+        assert_eq!(distance(id('B', 'B'), id('C', 'C')), 1);
+        assert_eq!(distance(id('B', 'B'), id('D', 'D')), 2);
+        assert_eq!(distance(id('B', 'B'), id('E', 'E')), 2);
+        assert_eq!(distance(id('D', 'D'), id('C', 'C')), 2);
+    }
+
+    #[test]
+    fn example_tunnels() {
+        let caves = CaveSystem::from_str(EXAMPLE);
+        let id = |a: char, b: char| caves.cave_by_name(CaveName(a, b)).unwrap();
+
+        assert_eq!(caves.caves.len(), 10);
+        assert_eq!(
+            caves.caves[id('A', 'A').0].tunnels,
+            vec![id('D', 'D'), id('I', 'I'), id('B', 'B')]
+        );
+        assert_eq!(caves.caves[id('H', 'H').0].tunnels, vec![id('G', 'G')]);
+        assert_eq!(
+            caves.caves_with_working_valve,
+            [
+                ('B', 'B'),
+                ('C', 'C'),
+                ('D', 'D'),
+                ('E', 'E'),
+                ('H', 'H'),
+                ('J', 'J')
+            ]
+            .map(|(a, b)| id(a, b))
+        );
+    }
+
     #[test]
     fn example_pathfinding() {
-        let caves = CaveSystem::from_str(EXAMPLE_INPUT);
+        let caves = CaveSystem::from_str(EXAMPLE);
         let start_cave = caves.cave_by_name(START_CAVE).unwrap();
         let c = caves.caves.get(start_cave.0).unwrap();
 
@@ -609,4 +1094,26 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
                 assert_eq!(*c.paths.get(neighbour_cave_id.0).unwrap(), 1);
             });
     }
+
+    #[test]
+    fn parse_example() {
+        let caves = parse(EXAMPLE).ok().unwrap();
+
+        assert_eq!(caves.caves.len(), 10);
+        assert_eq!(caves.caves_with_working_valve.len(), 6);
+
+        let aa = caves.cave_by_name(CaveName('A', 'A')).unwrap();
+        let jj = caves.cave_by_name(CaveName('J', 'J')).unwrap();
+        assert_eq!(caves.caves[jj.0].flow_rate, 21);
+        assert_eq!(caves.caves[aa.0].paths[jj.0], 2);
+    }
+
+    #[test]
+    fn solve_matches_parse_then_solve_parsed() {
+        let parsed = parse(EXAMPLE).ok().unwrap();
+        super::super::tests::assert_day_output_eq(
+            &super::solve(EXAMPLE).ok().unwrap(),
+            &solve_parsed(&parsed),
+        );
+    }
 }
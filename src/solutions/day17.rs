@@ -5,7 +5,7 @@ use std::{
 
 use crate::vec2d::Vec2D;
 
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 const CAVE_WIDTH: i64 = 7;
 const ROCK_VERTICAL_SPAWN_OFFSET: i64 = 3;
@@ -75,6 +75,30 @@ static ROCK_CUBE: Rock = Rock {
 
 static ROCKS: [&Rock; 5] = [&ROCK_MINUS, &ROCK_PLUS, &ROCK_L, &ROCK_PIPE, &ROCK_CUBE];
 
+/// Order in which rocks fall, starting over once every rock has fallen
+#[derive(Clone, Copy)]
+struct RockSequence(&'static [&'static Rock<'static>]);
+
+impl Default for RockSequence {
+    fn default() -> Self {
+        Self(&ROCKS)
+    }
+}
+
+impl RockSequence {
+    fn get(&self, index: usize) -> &'static Rock<'static> {
+        self.0[index]
+    }
+
+    fn next_rock(&self, index: usize) -> usize {
+        (index + 1) % self.0.len()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
 struct Rock<'a> {
     blocks: &'a [Vec2D<i64>],
     width: i64,
@@ -82,7 +106,7 @@ struct Rock<'a> {
 }
 
 #[derive(Clone, Copy)]
-enum Jet {
+pub(crate) enum Jet {
     Left,
     Right,
 }
@@ -114,6 +138,7 @@ struct RockTower<'a> {
     jet_iter_pos: usize,
     rocks_to_rest: i64,
     jets: &'a [Jet],
+    rocks: RockSequence,
     floor_map: HashMap<FloorShape, Block>,
     inhibit_superblock: bool, // board: Board,
                               // rock_iter:
@@ -123,9 +148,14 @@ struct RockTower<'a> {
 
 impl<'a> RockTower<'a> {
     fn new(rocks_to_rest: i64, jets: &'a [Jet]) -> Self {
+        Self::with_rocks(RockSequence::default(), rocks_to_rest, jets)
+    }
+
+    fn with_rocks(rocks: RockSequence, rocks_to_rest: i64, jets: &'a [Jet]) -> Self {
         Self {
             rocks_to_rest,
             jets,
+            rocks,
             floor_map: HashMap::new(),
             inhibit_superblock: false,
             rock_iter_pos: 0,
@@ -136,7 +166,7 @@ impl<'a> RockTower<'a> {
     }
 
     fn next_rock(&mut self) -> usize {
-        (self.rock_iter_pos + 1) % ROCKS.len()
+        self.rocks.next_rock(self.rock_iter_pos)
     }
 
     fn next_jet(&mut self) -> Jet {
@@ -145,26 +175,18 @@ impl<'a> RockTower<'a> {
         *jet
     }
 
-    fn remaining_rocks(&self, board: &Board) -> i64 {
-        self.rocks_to_rest - board.resting_rock_count
-    }
-
     fn block_size(&self) -> usize {
-        self.jets.len() * ROCKS.len()
+        self.jets.len() * self.rocks.len()
     }
 
     fn calc_tower_height(&mut self) -> i64 {
-        let mut board = Board::new(0); // Block here doesn't matter, run_block runs its own iter if needed
-
         println!(
             "Block size {}x{}={}",
             self.jets.len(),
-            ROCKS.len(),
+            self.rocks.len(),
             self.block_size()
         );
 
-        let mut jet_index = 0;
-
         // Block only
         // while self.remaining_rocks(&board) > self.block_size() as i64 && !self.inhibit_superblock {
         //     // println!("Running block");
@@ -172,7 +194,15 @@ impl<'a> RockTower<'a> {
         //     println!("Stack height now {}", board.stack_height)
         // }
 
-        while self.remaining_rocks(&board) > 0 {
+        self.height_after(self.rocks_to_rest)
+    }
+
+    /// Height of the tower once k rocks have come to rest, simulated from an empty cave
+    fn height_after(&self, k: i64) -> i64 {
+        let mut board = Board::with_rocks(self.rocks, 0); // Block here doesn't matter, run_block runs its own iter if needed
+        let mut jet_index = 0;
+
+        while board.resting_rock_count < k {
             let jet = *self.jets.get(jet_index).unwrap();
 
             jet_index = (jet_index + 1) % self.jets.len();
@@ -235,7 +265,10 @@ struct Board {
     /// Floor shape
     field: FloorShape,
 
-    /// Currently falling rock
+    /// Shapes to drop, in order
+    rocks: RockSequence,
+
+    /// Currently falling rock, indexing rocks
     falling_rock: usize,
 
     /// Position of the bottomleft corner of the falling rock
@@ -254,6 +287,42 @@ struct Board {
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let top_y = self.falling_rock_position.y + self.rock().height + 1;
+        self.write_rows(f, top_y, true)
+    }
+}
+
+impl Board {
+    #[cfg(test)]
+    fn new(start_rock: usize) -> Self {
+        Self::with_rocks(RockSequence::default(), start_rock)
+    }
+
+    fn with_rocks(rocks: RockSequence, start_rock: usize) -> Self {
+        let mut a = Self {
+            field: [0, 0, 0, 0, 0, 0, 0],
+            rocks,
+            falling_rock: start_rock,
+            falling_rock_position: Vec2D { x: 2, y: 4 },
+            top: 0,
+            resting_rock_count: 0,
+            stack_height: 0,
+        };
+        a.set_start_position();
+
+        a
+    }
+
+    /// Renders only the settled tower up to its top, ignoring the falling rock
+    #[cfg(test)]
+    fn render_settled(&self) -> String {
+        let mut out = String::new();
+        self.write_rows(&mut out, self.top, false)
+            .expect("Writing to a string should not fail");
+        out
+    }
+
+    // Writes rows top_y-1 down to 0, resting rock takes precedence over the falling rock
+    fn write_rows(&self, f: &mut impl Write, top_y: i64, show_rock: bool) -> std::fmt::Result {
         for n in 0..top_y {
             let y = top_y - (n + 1);
 
@@ -262,12 +331,13 @@ impl Display for Board {
                 let charpos = Vec2D { x, y };
                 if *self.field.get(charpos.x as usize).unwrap() > charpos.y {
                     f.write_char('#')?;
-                } else if self
-                    .rock()
-                    .blocks
-                    .iter()
-                    .map(|pos| (*pos + self.falling_rock_position))
-                    .any(|pos| pos == charpos)
+                } else if show_rock
+                    && self
+                        .rock()
+                        .blocks
+                        .iter()
+                        .map(|pos| (*pos + self.falling_rock_position))
+                        .any(|pos| pos == charpos)
                 {
                     f.write_char('@')?;
                 } else {
@@ -280,25 +350,9 @@ impl Display for Board {
 
         f.write_str("+-------+")
     }
-}
-
-impl Board {
-    fn new(start_rock: usize) -> Self {
-        let mut a = Self {
-            field: [0, 0, 0, 0, 0, 0, 0],
-            falling_rock: start_rock,
-            falling_rock_position: Vec2D { x: 2, y: 4 },
-            top: 0,
-            resting_rock_count: 0,
-            stack_height: 0,
-        };
-        a.set_start_position();
-
-        a
-    }
 
     fn rock(&self) -> &'static Rock<'static> {
-        ROCKS.get(self.falling_rock).unwrap()
+        self.rocks.get(self.falling_rock)
     }
 
     fn set_start_position(&mut self) {
@@ -307,7 +361,7 @@ impl Board {
     }
 
     fn next_rock(&self) -> usize {
-        (self.falling_rock + 1) % ROCKS.len()
+        self.rocks.next_rock(self.falling_rock)
     }
 
     fn advance(&mut self, jet: Jet) {
@@ -402,28 +456,42 @@ impl Board {
     }
 }
 
+pub(crate) const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
 // https://adventofcode.com/2022/day/17
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let jets: Vec<Jet> = input
+    Ok(solve_parsed(&parse(input)))
+}
+
+// Parsing is split from solving so both can be timed separately
+pub(crate) fn parse(input: &str) -> Vec<Jet> {
+    input
         .chars()
         .filter(|c| *c != '\n')
         .map(char::into)
-        .collect();
+        .collect()
+}
 
-    // unimplemented!();
+// Part 2 isn't solved yet
+pub(crate) fn solve_parsed(jets: &[Jet]) -> DayOutput {
+    let mut tower = RockTower::new(2022, jets);
+    let tower_height = tower.calc_tower_height();
 
-    let mut p1_tower = RockTower::new(2022, jets.as_slice());
-    let mut p2_tower = RockTower::new(1_000_000_000_000, jets.as_slice());
+    DayOutput::two(Some(PartResult::UInt(tower_height as u64)), None)
+}
+
+pub struct Day17;
 
-    let tower_height = p1_tower.calc_tower_height();
-    // let tower_height_p2 = p2_tower.calc_tower_height();
+impl Solution for Day17 {
+    const DAY: u32 = 17;
 
-    let tower_height_p2 = 0;
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
 
-    Ok(DayOutput {
-        part1: Some(PartResult::UInt(tower_height as u64)),
-        part2: Some(PartResult::UInt(tower_height_p2 as u64)),
-    })
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 // fn count_tower_height(jets: &[Jet], rock_fall_count: i64) -> i64 {}
@@ -431,20 +499,146 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
 #[cfg(test)]
 mod tests {
 
-    use crate::solutions::day17::RockTower;
+    use crate::{solutions::day17::RockTower, vec2d::Vec2D};
 
-    use super::Jet;
-
-    static EXAMPLE_INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+    use super::{parse, solve_parsed, Board, Jet, Rock, RockSequence, EXAMPLE, ROCKS, ROCK_MINUS};
 
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(17, super::solve)
     }
 
+    #[test]
+    fn rock_dimensions() {
+        for (index, rock) in ROCKS.iter().enumerate() {
+            let width = rock.blocks.iter().map(|b| b.x).max().unwrap() + 1;
+            let height = rock.blocks.iter().map(|b| b.y).max().unwrap() + 1;
+
+            assert_eq!(rock.width, width, "Width mismatch for rock {index}");
+            assert_eq!(rock.height, height, "Height mismatch for rock {index}");
+            assert!(
+                rock.blocks.iter().all(|b| b.x >= 0 && b.y >= 0),
+                "Rock {index} should be anchored at its bottom left corner"
+            );
+        }
+    }
+
+    #[test]
+    fn jet_blocked_by_walls() {
+        // The minus rock is 4 wide, it fits between x 0 and 3
+        let mut board = Board::new(0);
+
+        board.falling_rock_position.x = 0;
+        board.apply_jet(Jet::Left);
+        assert_eq!(board.falling_rock_position.x, 0);
+
+        board.apply_jet(Jet::Right);
+        assert_eq!(board.falling_rock_position.x, 1);
+
+        board.falling_rock_position.x = 3;
+        board.apply_jet(Jet::Right);
+        assert_eq!(board.falling_rock_position.x, 3);
+    }
+
+    #[test]
+    fn jet_blocked_by_resting_rock() {
+        let mut board = Board::new(3); // Pipe
+        board.field = [0, 0, 0, 0, 0, 0, 3];
+        board.falling_rock_position.x = 5;
+        board.falling_rock_position.y = 2;
+
+        board.apply_jet(Jet::Right);
+        assert_eq!(board.falling_rock_position.x, 5);
+
+        // Clear of the resting column once it is at the same height
+        board.falling_rock_position.y = 3;
+        board.apply_jet(Jet::Right);
+        assert_eq!(board.falling_rock_position.x, 6);
+    }
+
+    #[test]
+    fn can_fall_until_floor() {
+        let mut board = Board::new(0);
+        assert_eq!(board.falling_rock_position, Vec2D { x: 2, y: 3 });
+
+        for _ in 0..3 {
+            assert!(board.can_fall());
+            board.fall();
+        }
+
+        assert_eq!(board.falling_rock_position.y, 0);
+        assert!(!board.can_fall());
+    }
+
+    #[test]
+    fn can_fall_onto_resting_rock() {
+        let mut board = Board::new(0);
+        board.field = [0, 0, 0, 2, 0, 0, 0];
+        board.falling_rock_position.y = 3;
+
+        assert!(board.can_fall());
+        board.fall();
+        assert!(!board.can_fall());
+    }
+
+    #[test]
+    fn rest_on_floor() {
+        let mut board = Board::new(0);
+        board.falling_rock_position.y = 0;
+
+        board.rest();
+
+        assert_eq!(board.field, [0, 0, 1, 1, 1, 1, 0]);
+        assert_eq!(board.top, 1);
+        assert_eq!(board.stack_height, 0);
+        assert_eq!(board.resting_rock_count, 1);
+    }
+
+    #[test]
+    fn rest_normalizes_field() {
+        let mut board = Board::new(3); // Pipe
+        board.field = [2, 2, 2, 2, 2, 2, 1];
+        board.top = 2;
+        board.falling_rock_position = Vec2D { x: 6, y: 1 };
+
+        board.rest();
+
+        // The pipe lifts the lowest column, so the whole field shifts down by 2
+        assert_eq!(board.field, [0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(board.top, 3);
+        assert_eq!(board.stack_height, 2);
+    }
+
+    #[test]
+    fn example_height_after() {
+        let jets: Vec<Jet> = EXAMPLE.chars().map(|c| c.into()).collect();
+        let tower = RockTower::new(2022, jets.as_slice());
+
+        assert_eq!(tower.height_after(0), 0);
+        assert_eq!(tower.height_after(1), 1);
+        assert_eq!(tower.height_after(2), 4);
+        assert_eq!(tower.height_after(3), 6);
+        assert_eq!(tower.height_after(10), 17);
+        assert_eq!(tower.height_after(2022), 3068);
+    }
+
+    #[test]
+    fn minus_rocks_only() {
+        static MINUS_ONLY: [&Rock; 1] = [&ROCK_MINUS];
+
+        // Pushed against a wall every time, each bar lands flat on the one before
+        for jet in ["<", ">"] {
+            let jets: Vec<Jet> = jet.chars().map(|c| c.into()).collect();
+            let mut tower = RockTower::with_rocks(RockSequence(&MINUS_ONLY), 20, jets.as_slice());
+
+            assert_eq!(tower.height_after(5), 5);
+            assert_eq!(tower.calc_tower_height(), 20);
+        }
+    }
+
     #[test]
     fn example() {
-        let jets: Vec<Jet> = EXAMPLE_INPUT
+        let jets: Vec<Jet> = EXAMPLE
             .chars()
             .filter(|c| *c != '\n')
             .map(|c| c.into())
@@ -459,7 +653,7 @@ mod tests {
 
     #[test]
     fn example_superblock() {
-        let jets: Vec<Jet> = EXAMPLE_INPUT
+        let jets: Vec<Jet> = EXAMPLE
             .chars()
             .filter(|c| *c != '\n')
             .map(|c| c.into())
@@ -473,7 +667,7 @@ mod tests {
 
     // /    #[test]
     // fn superblock_parity() {
-    //     let jets: Vec<Jet> = EXAMPLE_INPUT
+    //     let jets: Vec<Jet> = EXAMPLE
     //         .chars()
     //         .filter(|c| *c != '\n')
     //         .map(|c| c.into())
@@ -488,4 +682,42 @@ mod tests {
 
     //     assert_eq!(real_tower_height, superblock_tower_height);
     // }
+
+    #[test]
+    fn render_settled_board() {
+        let mut board = Board::new(0);
+        board.field = [1, 1, 2, 0, 0, 0, 0];
+        board.top = 2;
+
+        assert_eq!(board.render_settled(), "|..#....|\n|###....|\n+-------+");
+
+        // Falling rock overlapping the tower still renders the tower
+        board.falling_rock_position = Vec2D { x: 0, y: 1 };
+        let rendering = board.to_string();
+        assert!(
+            rendering.ends_with("|@@#@...|\n|###....|\n+-------+"),
+            "{rendering}"
+        );
+    }
+
+    #[test]
+    fn parse_example() {
+        let jets = parse(EXAMPLE);
+
+        assert_eq!(jets.len(), 40);
+        assert!(matches!(jets[0], Jet::Right));
+        assert!(matches!(jets[3], Jet::Left));
+
+        // The trailing newline of an input file is not a jet
+        assert_eq!(parse(">><\n").len(), 3);
+    }
+
+    #[test]
+    fn solve_matches_parse_then_solve_parsed() {
+        let parsed = parse(EXAMPLE);
+        super::super::tests::assert_day_output_eq(
+            &super::solve(EXAMPLE).ok().unwrap(),
+            &solve_parsed(&parsed),
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use crate::solutions::DayOutput;
 
-use super::{LogicError, PartResult};
+use super::{LogicError, PartResult, Solution};
 
 #[derive(Debug)]
 struct GuideLine(i32, i32);
@@ -34,10 +34,15 @@ impl From<&str> for GuideLine {
     }
 }
 
+pub(crate) const EXAMPLE: &str = "A Y
+B X
+C Z
+";
+
 // https://adventofcode.com/2022/day/2
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let lines: Vec<GuideLine> = input
-        .split('\n')
+        .lines() // Also strips the \r of CRLF line endings
         .filter(|s| s.len() == 3)
         .map(GuideLine::from)
         .collect();
@@ -45,14 +50,31 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let part1 = lines.iter().map(GuideLine::score_p1).sum();
     let part2 = lines.iter().map(GuideLine::score_p2).sum();
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(part1)),
-        part2: Some(PartResult::Int(part2)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(part1)),
+        Some(PartResult::Int(part2)),
+    ))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::solutions::tests::assert_day_output_eq;
+    use crate::solutions::{DayOutput, PartResult};
+
     #[test]
     fn test_radial_dif() {
         assert_eq!(super::radial_dif(2, 1), 1);
@@ -86,6 +108,35 @@ mod tests {
         assert_eq!(g3.score_p2(), 7);
     }
 
+    #[test]
+    fn all_combinations() {
+        // (line, part 1 score, part 2 score)
+        #[rustfmt::skip]
+        let table = [
+            ("A X", 4, 3), ("A Y", 8, 4), ("A Z", 3, 8),
+            ("B X", 1, 1), ("B Y", 5, 5), ("B Z", 9, 9),
+            ("C X", 7, 2), ("C Y", 2, 6), ("C Z", 6, 7),
+        ];
+
+        for (line, p1, p2) in table {
+            let guide: super::GuideLine = line.into();
+            assert_eq!(guide.score_p1(), p1, "Part 1 score for {line}");
+            assert_eq!(guide.score_p2(), p2, "Part 2 score for {line}");
+        }
+    }
+
+    #[test]
+    fn crlf_input() {
+        let output = super::solve(&super::EXAMPLE.replace('\n', "\r\n"))
+            .ok()
+            .unwrap();
+
+        assert_day_output_eq(
+            &DayOutput::two(Some(PartResult::Int(15)), Some(PartResult::Int(12))),
+            &output,
+        );
+    }
+
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(2, super::solve)
@@ -1,35 +1,37 @@
 use crate::solutions::DayOutput;
 use crate::solutions::PartResult;
 
-use super::LogicError;
+use super::{LogicError, Solution};
 
 use std::convert::TryFrom;
 
 struct Rucksack {
-    full_string: String,
-    left: String,
-    right: String,
+    contents: String,
 }
 
-fn char_priority(c: char) -> Option<i32> {
-    if c.is_uppercase() {
-        Some(c.to_digit(36)? + 17)
-    } else {
-        Some(c.to_digit(36)? - 9)
+// a-z are 1 through 26, A-Z are 27 through 52, anything else has no priority
+const fn item_priority(b: u8) -> Option<i32> {
+    match b {
+        b'a'..=b'z' => Some((b - b'a' + 1) as i32),
+        b'A'..=b'Z' => Some((b - b'A' + 27) as i32),
+        _ => None,
     }
-    .and_then(|u| u.try_into().ok())
 }
 
 impl Rucksack {
+    fn left(&self) -> &str {
+        &self.contents[..self.contents.len() / 2]
+    }
+
+    fn right(&self) -> &str {
+        &self.contents[self.contents.len() / 2..]
+    }
+
     fn priority_item_value(&self) -> Option<i32> {
-        self.left
-            .chars()
-            .find_map(|left_char| {
-                self.right
-                    .chars()
-                    .find(|right_char| (left_char == *right_char))
-            })
-            .and_then(char_priority)
+        self.left()
+            .bytes()
+            .find(|left_byte| self.right().as_bytes().contains(left_byte))
+            .and_then(item_priority)
     }
 }
 
@@ -37,33 +39,33 @@ impl TryFrom<&str> for Rucksack {
     type Error = ();
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mid = value.len() / 2;
-        let (left, right) = value.split_at(mid);
-
         Ok(Self {
-            full_string: value.to_owned(),
-            left: left.to_owned(),
-            right: right.to_owned(),
+            contents: value.to_owned(),
         })
     }
 }
 
-fn find_badge(sacks: &[Rucksack]) -> char {
-    let mut s: Vec<String> = vec![String::new(), String::new(), String::new()];
-    let s2: Vec<String> = sacks.iter().map(|f| f.full_string.clone()).collect();
-
-    s[0..3].clone_from_slice(&s2[0..3]);
+fn find_badge(sacks: &[Rucksack]) -> u8 {
+    let mut s: Vec<&str> = sacks[0..3].iter().map(|f| f.contents.as_str()).collect();
 
-    s.sort_by_key(String::len);
+    s.sort_by_key(|contents| contents.len());
 
     let smallest = &s[0];
 
     smallest
-        .chars()
-        .find(|ch| s.iter().skip(1).all(|sack| sack.contains(*ch)))
+        .bytes()
+        .find(|b| s.iter().skip(1).all(|sack| sack.as_bytes().contains(b)))
         .expect("Smallest character")
 }
 
+pub(crate) const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
 // https://adventofcode.com/2022/day/3
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let rucksacks: Result<Vec<Rucksack>, ()> = input.lines().map(TryInto::try_into).collect();
@@ -79,30 +81,46 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let badge_sum: i32 = rucksacks
         .chunks(3)
         .map(find_badge)
-        .filter_map(char_priority)
+        .filter_map(item_priority)
         .sum();
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(priority_item_sum)),
-        part2: Some(PartResult::Int(badge_sum)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(priority_item_sum)),
+        Some(PartResult::Int(badge_sum)),
+    ))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Rucksack;
+    use super::{item_priority, Rucksack, EXAMPLE};
 
     #[test]
     fn example() -> Result<(), ()> {
+        let mut lines = EXAMPLE.lines();
+
         assert_eq!(
-            TryInto::<Rucksack>::try_into("vJrwpWtwJgWrhcsFMMfFFhFp")?
+            TryInto::<Rucksack>::try_into(lines.next().ok_or(())?)?
                 .priority_item_value()
                 .ok_or(())?,
             16
         );
 
         assert_eq!(
-            TryInto::<Rucksack>::try_into("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL")?
+            TryInto::<Rucksack>::try_into(lines.next().ok_or(())?)?
                 .priority_item_value()
                 .ok_or(())?,
             38
@@ -111,6 +129,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn split_halves() -> Result<(), ()> {
+        let rucksack: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".try_into()?;
+
+        assert_eq!(rucksack.left(), "vJrwpWtwJgWr");
+        assert_eq!(rucksack.right(), "hcsFMMfFFhFp");
+
+        Ok(())
+    }
+
+    #[test]
+    fn priorities() {
+        assert_eq!(item_priority(b'a'), Some(1));
+        assert_eq!(item_priority(b'z'), Some(26));
+        assert_eq!(item_priority(b'A'), Some(27));
+        assert_eq!(item_priority(b'Z'), Some(52));
+
+        let all: Vec<i32> = (b'a'..=b'z')
+            .chain(b'A'..=b'Z')
+            .map(|b| item_priority(b).unwrap())
+            .collect();
+        assert_eq!(all, (1..=52).collect::<Vec<_>>());
+
+        assert_eq!(item_priority(b'0'), None);
+        assert_eq!(item_priority(b'9'), None);
+        assert_eq!(item_priority(b'`'), None);
+        assert_eq!(item_priority(b'['), None);
+    }
+
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(3, super::solve)
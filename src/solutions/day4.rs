@@ -1,7 +1,8 @@
+use crate::range::Ranging;
 use crate::solutions::DayOutput;
 use crate::solutions::PartResult;
 
-use super::LogicError;
+use super::{LogicError, Solution};
 
 struct Range {
     lower: i32, //Inclusive
@@ -61,8 +62,47 @@ impl Range {
     fn overlaps(&self, other: &Self) -> bool {
         !(self.upper <= other.lower || self.lower >= other.upper)
     }
+
+    // First and last section, both inclusive
+    fn sections(&self) -> (i32, i32) {
+        (self.lower, self.upper - 1)
+    }
 }
 
+// Pairs whose assignments share no section at all
+// Neither this nor total_overlap_sections is needed for the answers, they're kept for questions beyond the puzzle
+#[allow(dead_code)]
+fn disjoint_count(pairs: &[Pair]) -> i32 {
+    pairs
+        .iter()
+        .map(|pair| i32::from(!pair.left.sections().overlaps(&pair.right.sections())))
+        .sum()
+}
+
+// Sections assigned to both elves, summed over all pairs
+#[allow(dead_code)]
+fn total_overlap_sections(pairs: &[Pair]) -> i32 {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (left, right) = (pair.left.sections(), pair.right.sections());
+            if left.overlaps(&right) {
+                left.overlap(&right).range_size() + 1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+pub(crate) const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
 // https://adventofcode.com/2022/day/4
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let pairs: Vec<Pair> = input
@@ -86,17 +126,49 @@ pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
 
     // pairs.map(|)
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(contained_pair_count)),
-        part2: Some(PartResult::Int(overlapping_pair_count)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(contained_pair_count)),
+        Some(PartResult::Int(overlapping_pair_count)),
+    ))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{disjoint_count, total_overlap_sections, Pair, EXAMPLE};
+
+    fn parse_example_input() -> Vec<Pair> {
+        EXAMPLE
+            .lines()
+            .map(|p| Pair::try_from(p).expect("succesful parse"))
+            .collect()
+    }
 
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(4, super::solve)
     }
+
+    #[test]
+    fn example_disjoint_count() {
+        assert_eq!(disjoint_count(&parse_example_input()), 2);
+    }
+
+    #[test]
+    fn example_total_overlap_sections() {
+        assert_eq!(total_overlap_sections(&parse_example_input()), 10);
+    }
 }
@@ -6,10 +6,12 @@ use std::str::FromStr;
 use crate::solutions::DayOutput;
 use crate::solutions::PartResult;
 
-use super::LogicError;
+use crate::parsing::blocks;
+
+use super::{LogicError, Solution};
 
 // "move 2 from 4 to 2"
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 struct Command {
     count: i32,
     origin: i32,
@@ -98,33 +100,85 @@ impl FromStr for Stacks {
     }
 }
 
-// https://adventofcode.com/2022/day/5
-pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let (stack_str, command_str) = input.split_once("\n\n").expect("input to contain newlines");
+// The starting stacks followed by the crane commands
+struct Puzzle(Stacks, Vec<Command>);
 
-    let commands: Vec<Command> = command_str
-        .lines()
-        .map(str::parse)
-        .map(|o| o.expect("valid command"))
-        .collect();
+impl FromStr for Puzzle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sections = blocks(s);
+        let stacks = sections.next().ok_or(())?.parse()?;
+        let commands = sections
+            .next()
+            .ok_or(())?
+            .lines()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
 
-    let mut part1_stack: Stacks = stack_str.parse().expect("succesful parse");
+        Ok(Self(stacks, commands))
+    }
+}
+
+pub(crate) const EXAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2\n";
+
+// https://adventofcode.com/2022/day/5
+pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
+    let Puzzle(mut part1_stack, commands) = input.parse().expect("succesful parse");
     let mut part2_stack: Stacks = part1_stack.clone();
 
-    execute_p1_crane_commands(&mut part1_stack, &commands);
+    execute_p1_crane_commands(&mut part1_stack, &commands)?;
     let p1 = part1_stack.print_top_stack();
 
-    execute_p2_crane_commands(&mut part2_stack, &commands);
+    execute_p2_crane_commands(&mut part2_stack, &commands)?;
     let p2 = part2_stack.print_top_stack();
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Str(p1)),
-        part2: Some(PartResult::Str(p2)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Str(p1)),
+        Some(PartResult::Str(p2)),
+    ))
 }
 
-fn execute_p1_crane_commands(s: &mut Stacks, commands: &[Command]) {
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
+}
+
+// Ensures the command refers to existing stacks and doesn't try to move more crates than the origin stack holds
+fn check_command(s: &Stacks, command: &Command) -> Result<(), LogicError> {
+    if s.0.get(command.destination as usize).is_none() {
+        return Err(LogicError(format!(
+            "Command {command:?} refers to a missing destination stack"
+        )));
+    }
+
+    let stack_height =
+        s.0.get(command.origin as usize)
+            .ok_or_else(|| LogicError(format!("Command {command:?} refers to a missing stack")))?
+            .len();
+
+    if command.count as usize > stack_height {
+        return Err(LogicError(format!(
+            "Command {command:?} moves more crates than the {stack_height} on the origin stack"
+        )));
+    }
+
+    Ok(())
+}
+
+fn execute_p1_crane_commands(s: &mut Stacks, commands: &[Command]) -> Result<(), LogicError> {
     for command in commands {
+        check_command(s, command)?;
+
         for _ in 0..command.count {
             let container =
                 s.0.index_mut(command.origin as usize)
@@ -134,10 +188,14 @@ fn execute_p1_crane_commands(s: &mut Stacks, commands: &[Command]) {
             s.0.index_mut(command.destination as usize).push(container);
         }
     }
+
+    Ok(())
 }
 
-fn execute_p2_crane_commands(s: &mut Stacks, commands: &[Command]) {
+fn execute_p2_crane_commands(s: &mut Stacks, commands: &[Command]) -> Result<(), LogicError> {
     for command in commands {
+        check_command(s, command)?;
+
         let mut arm_stack = vec![];
         for _ in 0..command.count {
             arm_stack.push(
@@ -154,13 +212,75 @@ fn execute_p2_crane_commands(s: &mut Stacks, commands: &[Command]) {
             s.0.index_mut(command.destination as usize).push(c);
         }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{
+        execute_p1_crane_commands, execute_p2_crane_commands, Command, Puzzle, Stacks, EXAMPLE,
+    };
 
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(5, super::solve)
     }
+
+    #[test]
+    fn move_too_many_crates() {
+        let mut stacks = Stacks(vec![vec![b'A', b'B'], vec![b'C']]);
+        let commands: Vec<Command> = vec!["move 3 from 1 to 2".parse().unwrap()];
+
+        let err = execute_p1_crane_commands(&mut stacks.clone(), &commands).unwrap_err();
+        assert!(err.0.contains("count: 3"), "{}", err.0);
+
+        assert!(execute_p2_crane_commands(&mut stacks, &commands).is_err());
+    }
+
+    #[test]
+    fn move_to_missing_stack() {
+        let mut stacks = Stacks(vec![vec![b'A', b'B'], vec![b'C']]);
+        let commands: Vec<Command> = vec!["move 1 from 1 to 3".parse().unwrap()];
+
+        let err = execute_p1_crane_commands(&mut stacks.clone(), &commands).unwrap_err();
+        assert!(err.0.contains("missing destination stack"), "{}", err.0);
+
+        assert!(execute_p2_crane_commands(&mut stacks, &commands).is_err());
+    }
+
+    #[test]
+    fn move_all_crates() {
+        let mut stacks = Stacks(vec![vec![b'A', b'B'], vec![b'C']]);
+        let commands: Vec<Command> = vec!["move 2 from 1 to 2".parse().unwrap()];
+
+        assert!(execute_p1_crane_commands(&mut stacks, &commands).is_ok());
+        assert_eq!(stacks.0, vec![vec![], vec![b'C', b'B', b'A']]);
+    }
+
+    #[test]
+    fn parse_puzzle() {
+        let Puzzle(stacks, commands) = EXAMPLE.parse().unwrap();
+
+        assert_eq!(
+            stacks.0,
+            vec![vec![b'Z', b'N'], vec![b'M', b'C', b'D'], vec![b'P']]
+        );
+        assert_eq!(commands.len(), 4);
+        assert_eq!(
+            commands[..2],
+            [
+                Command {
+                    count: 1,
+                    origin: 1,
+                    destination: 0
+                },
+                Command {
+                    count: 3,
+                    origin: 0,
+                    destination: 2
+                },
+            ]
+        );
+    }
 }
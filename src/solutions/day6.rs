@@ -1,4 +1,4 @@
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 fn find_first_unique_character_window(haystack: &str, window_size: usize) -> Option<i32> {
     let b = haystack.as_bytes();
@@ -27,31 +27,40 @@ fn has_unqiue_characters(slice: &[u8]) -> bool {
     true
 }
 
+pub(crate) const EXAMPLE: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
 // https://adventofcode.com/2022/day/6
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let p1 = find_first_unique_character_window(input, 4).expect("valid input");
     let p2 = find_first_unique_character_window(input, 14).expect("valid input");
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(p1)),
-        part2: Some(PartResult::Int(p2)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(p1)),
+        Some(PartResult::Int(p2)),
+    ))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::find_first_unique_character_window;
+    use super::{find_first_unique_character_window, EXAMPLE};
 
     #[test]
     fn example1() {
-        assert_eq!(
-            find_first_unique_character_window(
-                "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string().as_str(),
-                4
-            )
-            .unwrap(),
-            7
-        );
+        assert_eq!(find_first_unique_character_window(EXAMPLE, 4).unwrap(), 7);
     }
 
     #[test]
@@ -1,14 +1,14 @@
-use std::{cell::OnceCell, collections::HashMap, str::FromStr};
+use std::{cell::OnceCell, collections::BTreeMap, str::FromStr};
 
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
 
 enum Node {
     File {
-        size: i32,
+        size: u64,
     },
     Folder {
-        size: OnceCell<i32>,
-        children: HashMap<String, Node>,
+        size: OnceCell<u64>,
+        children: BTreeMap<String, Node>,
     },
 }
 // Pops a directory from the end of the vector and move it into the new last entry in the vector
@@ -26,7 +26,7 @@ impl FromStr for Node {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let root = Self::Folder {
             size: OnceCell::new(),
-            children: HashMap::new(),
+            children: BTreeMap::new(),
         };
 
         // Vector of all opened folders
@@ -81,13 +81,13 @@ impl FromStr for Node {
 }
 
 impl Node {
-    fn new_file(size: i32) -> Self {
+    fn new_file(size: u64) -> Self {
         Self::File { size }
     }
     fn new_folder() -> Self {
         Self::Folder {
             size: OnceCell::new(), // Note ignoring the argument, unlike files, folder size is not known at creation. calc_size can figure that out
-            children: HashMap::new(),
+            children: BTreeMap::new(),
         }
     }
 
@@ -101,7 +101,7 @@ impl Node {
     }
 
     // Get own size or resursively get (and cache) children's size
-    fn calc_size(&self) -> i32 {
+    fn calc_size(&self) -> u64 {
         match self {
             Self::File { size, .. } => *size,
             Self::Folder { size, children, .. } => *size.get_or_init(|| {
@@ -113,6 +113,15 @@ impl Node {
         }
     }
 
+    // Walks down the children by name, an empty path is the node itself
+    #[allow(dead_code)]
+    fn get_path(&self, path: &[&str]) -> Option<&Self> {
+        path.iter().try_fold(self, |node, name| match node {
+            Self::File { .. } => None,
+            Self::Folder { children, .. } => children.get(*name),
+        })
+    }
+
     fn remove_child(&mut self, path: impl Into<String>) -> (String, Self) {
         match self {
             Self::File { .. } => panic!("File doesn't have children"),
@@ -148,7 +157,7 @@ impl FromStr for Command {
 }
 
 enum DirEntry {
-    File(String, i32),
+    File(String, u64),
     Dir(String),
 }
 
@@ -161,7 +170,7 @@ impl FromStr for DirEntry {
         if left == "dir" {
             Ok(Self::Dir(right.into()))
         } else {
-            let size: i32 = left.parse().expect("left side to parse into int");
+            let size: u64 = left.parse().expect("left side to parse into int");
             Ok(Self::File(right.into(), size))
         }
     }
@@ -184,27 +193,66 @@ impl FromStr for Line {
     }
 }
 
+pub(crate) const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
 // https://adventofcode.com/2022/day/7
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
     let fs: Node = input.parse().expect("Succesfull parse");
     let total_size = fs.calc_size();
 
-    let mut count: i32 = 0;
+    let mut count: u64 = 0;
     sum_size(&fs, &mut count);
 
     let del_size = find_dir_to_delete(&fs, total_size);
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(count)),
-        part2: Some(PartResult::Int(del_size)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::UInt(count)),
+        Some(PartResult::UInt(del_size)),
+    ))
 }
 
-fn sum_size(fs: &Node, count: &mut i32) {
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
+}
+
+fn sum_size(fs: &Node, count: &mut u64) {
     match fs {
         Node::File { .. } => (),
-        Node::Folder { size, children, .. } => {
-            let size = *size.get().expect("Size should be known at this point, if not NodeRef::calc_size should have been called first");
+        Node::Folder { children, .. } => {
+            let size = fs.calc_size();
 
             if size <= 100_000 {
                 *count += size;
@@ -216,20 +264,21 @@ fn sum_size(fs: &Node, count: &mut i32) {
     }
 }
 
-fn collect_fs_to_vec(fs: &Node, v: &mut Vec<i32>) {
+fn collect_fs_to_vec(fs: &Node, v: &mut Vec<u64>) {
     match fs {
         Node::File { .. } => (),
-        Node::Folder { size, children, .. } => {
-            v.push(*size.get().expect("size to exist"));
+        Node::Folder { children, .. } => {
+            v.push(fs.calc_size());
             children.iter().for_each(|f| collect_fs_to_vec(f.1, v));
         }
     }
 }
 
-fn find_dir_to_delete(fs: &Node, occupied_space: i32) -> i32 {
-    let storage_size = 70_000_000;
-    let current_free_space = storage_size - occupied_space;
-    let min_space_to_free = 30_000_000 - current_free_space;
+fn find_dir_to_delete(fs: &Node, occupied_space: u64) -> u64 {
+    let storage_size: u64 = 70_000_000;
+    let current_free_space = storage_size.saturating_sub(occupied_space);
+    let required_space: u64 = 30_000_000;
+    let min_space_to_free = required_space.saturating_sub(current_free_space);
 
     let mut dirs = vec![];
 
@@ -254,43 +303,106 @@ mod tests {
 
     #[test]
     fn example() -> Result<(), String> {
-        let input: String = vec![
+        let input = EXAMPLE;
+
+        let fs: Node = input.parse().expect("Succesfull parse");
+        let size = fs.calc_size();
+
+        assert_eq!(size, 48_381_165);
+
+        let mut count: u64 = 0;
+        sum_size(&fs, &mut count);
+
+        assert_eq!(count, 95437);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sizes_without_calc_size() {
+        let fs: Node = EXAMPLE.parse().expect("Succesfull parse");
+
+        let mut count: u64 = 0;
+        sum_size(&fs, &mut count);
+        assert_eq!(count, 95437);
+
+        let fs: Node = EXAMPLE.parse().expect("Succesfull parse");
+        let mut dirs = vec![];
+        collect_fs_to_vec(&fs, &mut dirs);
+        dirs.sort_unstable();
+        assert_eq!(dirs, vec![584, 94853, 24_933_642, 48_381_165]);
+    }
+
+    #[test]
+    fn folders_enumerate_in_name_order() {
+        let fs: Node = EXAMPLE.parse().expect("Succesfull parse");
+        let mut dirs = vec![];
+        collect_fs_to_vec(&fs, &mut dirs);
+
+        // Depth first, children by name: /, a, a/e, d
+        assert_eq!(dirs, vec![48_381_165, 94853, 584, 24_933_642]);
+    }
+
+    #[test]
+    fn sizes_beyond_i32() {
+        let input = [
             "$ cd /",
             "$ ls",
             "dir a",
-            "14848514 b.txt",
-            "8504156 c.dat",
-            "dir d",
+            "2000000000 b.bin",
             "$ cd a",
             "$ ls",
-            "dir e",
-            "29116 f",
-            "2557 g",
-            "62596 h.lst",
-            "$ cd e",
-            "$ ls",
-            "584 i",
-            "$ cd ..",
-            "$ cd ..",
-            "$ cd d",
-            "$ ls",
-            "4060174 j",
-            "8033020 d.log",
-            "5626152 d.ext",
-            "7214296 k",
+            "3000000000 c.bin",
         ]
         .join("\n");
 
         let fs: Node = input.parse().expect("Succesfull parse");
-        let size = fs.calc_size();
+        assert_eq!(fs.calc_size(), 5_000_000_000);
 
-        assert_eq!(size, 48_381_165);
+        let mut dirs = vec![];
+        collect_fs_to_vec(&fs, &mut dirs);
+        dirs.sort_unstable();
+        assert_eq!(dirs, vec![3_000_000_000, 5_000_000_000]);
+
+        assert_eq!(find_dir_to_delete(&fs, fs.calc_size()), 3_000_000_000);
+    }
+
+    #[test]
+    fn small_folder_sum_beyond_i32() {
+        // Every folder just makes the 100_000 cut, together they add up past i32::MAX
+        let folder_count = 25_000;
+        let mut lines = vec!["$ cd /".to_owned(), "$ ls".to_owned()];
+        lines.extend((0..folder_count).map(|n| format!("dir d{n}")));
+        for n in 0..folder_count {
+            lines.extend([
+                format!("$ cd d{n}"),
+                "$ ls".to_owned(),
+                "100000 f".to_owned(),
+                "$ cd ..".to_owned(),
+            ]);
+        }
 
-        let mut count: i32 = 0;
+        let fs: Node = lines.join("\n").parse().expect("Succesfull parse");
+        let mut count: u64 = 0;
         sum_size(&fs, &mut count);
 
-        assert_eq!(count, 95437);
+        assert_eq!(count, 2_500_000_000);
+        assert!(count > i32::MAX as u64);
+    }
 
-        Ok(())
+    #[test]
+    fn get_path() {
+        let fs: Node = EXAMPLE.parse().expect("Succesfull parse");
+
+        assert_eq!(fs.get_path(&[]).map(Node::calc_size), Some(48_381_165));
+        assert_eq!(fs.get_path(&["a"]).map(Node::calc_size), Some(94_853));
+        assert_eq!(fs.get_path(&["a", "e"]).map(Node::calc_size), Some(584));
+        assert_eq!(
+            fs.get_path(&["d", "k"]).map(Node::calc_size),
+            Some(7_214_296)
+        );
+
+        assert!(fs.get_path(&["a", "x"]).is_none());
+        assert!(fs.get_path(&["b.txt", "a"]).is_none());
     }
 }
@@ -1,41 +1,15 @@
-use std::collections::HashMap;
-
-use crate::grid::iterators::GridIterator;
 use crate::grid::iterators::GridLineIterator;
-use crate::grid::Direction;
 use crate::grid::Grid;
 use crate::vec2d::Vec2D;
 
-use super::{DayOutput, LogicError, PartResult};
-
-const TALLEST_TREE: u8 = b'9';
-
-struct SightlineIterator<'a> {
-    iter: GridLineIterator<'a, u8>,
-    max_height: i32,
-}
+use super::{DayOutput, LogicError, PartResult, Solution};
 
-impl<'a> Iterator for SightlineIterator<'a> {
-    type Item = (i32, &'a u8);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.iter.next() {
-            Some(entry) => {
-                if i32::from(*entry.1) >= self.max_height {
-                    None
-                } else {
-                    Some(entry)
-                }
-            }
-            None => None,
-        }
-    }
-}
+const TALLEST_TREE: u8 = 9;
 
 struct VisableTreeIterator<'a> {
     iter: GridLineIterator<'a, u8>,
     seen_first: bool,
-    highest_seen: i32,
+    highest_seen: u8,
 }
 
 impl<'a> VisableTreeIterator<'a> {
@@ -43,7 +17,7 @@ impl<'a> VisableTreeIterator<'a> {
         VisableTreeIterator {
             iter,
             seen_first: false,
-            highest_seen: -1,
+            highest_seen: 0,
         }
     }
 }
@@ -56,19 +30,19 @@ impl<'a> Iterator for VisableTreeIterator<'a> {
             return match self.iter.next() {
                 Some(entry) => {
                     self.seen_first = true;
-                    self.highest_seen = i32::from(*entry.1);
+                    self.highest_seen = *entry.1;
                     Some(entry)
                 }
                 None => None,
             };
         }
 
-        if self.highest_seen == i32::from(TALLEST_TREE) {
+        if self.highest_seen == TALLEST_TREE {
             return None;
         }
 
         for entry in self.iter.by_ref() {
-            let tree_height = i32::from(*entry.1);
+            let tree_height = *entry.1;
 
             if tree_height > self.highest_seen {
                 self.highest_seen = tree_height;
@@ -81,69 +55,149 @@ impl<'a> Iterator for VisableTreeIterator<'a> {
 }
 
 fn find_treehouse_spot(grid: &Grid<u8>) -> i32 {
-    GridIterator::new(grid.width(), grid.height())
-        .map(|position| score_treehouse_spot(grid, position))
+    treehouse_scores(grid)
+        .into_iter()
         // .inspect(|f| println!("{f}"))
-        .max() 
+        .max()
         .expect("number")
 }
 
-fn score_treehouse_spot(grid: &Grid<u8>, position: Vec2D<usize>) -> i32 {
-    let top_sightline_count = count_visible_trees(grid, position, Direction::Up);
-    let bottom_sightline_count = count_visible_trees(grid, position, Direction::Down);
-    let right_sightline_count = count_visible_trees(grid, position, Direction::Right);
-    let left_sightline_count = count_visible_trees(grid, position, Direction::Left);
+// Scores every spot in row-major order, using one linear sweep per direction
+fn treehouse_scores(grid: &Grid<u8>) -> Vec<i32> {
+    let (width, height) = (grid.width(), grid.height());
+    let mut scores = vec![1; width * height];
 
-    top_sightline_count * right_sightline_count * bottom_sightline_count * left_sightline_count
-}
+    for y in 0..height {
+        apply_viewing_distances(grid, &mut scores, (0..width).map(|x| Vec2D { x, y }));
+        apply_viewing_distances(grid, &mut scores, (0..width).rev().map(|x| Vec2D { x, y }));
+    }
 
-fn count_visible_trees(grid: &Grid<u8>, position: Vec2D<usize>, dir: Direction) -> i32 {
-    let mut a = grid.line_iter(position, dir);
+    for x in 0..width {
+        apply_viewing_distances(grid, &mut scores, (0..height).map(|y| Vec2D { x, y }));
+        apply_viewing_distances(grid, &mut scores, (0..height).rev().map(|y| Vec2D { x, y }));
+    }
 
-    let max_tree_size = *a.next().expect("First tree to be valid").1; // Skip the starting tile and use it as height cap
+    scores
+}
 
-    let mut count = 0;
+// Multiplies the viewing distance looking back along the line into each score
+// The line should start at an edge
+fn apply_viewing_distances(
+    grid: &Grid<u8>,
+    scores: &mut [i32],
+    line: impl Iterator<Item = Vec2D<usize>>,
+) {
+    // Distance from the edge where a tree of each height was last seen, the edge itself counts as 0
+    let mut last_seen = [0; 10];
+
+    for (distance, position) in line.enumerate() {
+        let tree_height = usize::from(
+            *grid
+                .get(position.x, position.y)
+                .expect("Line to stay on the grid"),
+        );
 
-    for entry in a {
-        count += 1;
-        let tree_height = *entry.1;
+        let blocked_at = *last_seen[tree_height..]
+            .iter()
+            .max()
+            .expect("Slice to be non-empty");
 
-        if tree_height >= max_tree_size {
-            break;
-        }
+        scores[position.x + position.y * grid.width()] *= (distance - blocked_at) as i32;
+        last_seen[tree_height] = distance;
     }
-
-    count
 }
 
-fn count_trees(grid: &Grid<u8>) -> i32 {
-    let mut seen_trees = HashMap::new();
+// Marks every tree visible from outside the grid, indexed like the grid itself
+fn visible_trees(grid: &Grid<u8>) -> Vec<bool> {
+    let mut seen_trees = vec![false; grid.width() * grid.height()];
 
     for peek in grid.edges() {
-        VisableTreeIterator::new(peek).for_each(|tree| {
-            seen_trees.insert(tree.0, true);
+        VisableTreeIterator::new(peek).for_each(|(index, _)| {
+            seen_trees[index as usize] = true;
         });
     }
 
-    seen_trees.len() as i32
+    seen_trees
+}
+
+fn count_trees(grid: &Grid<u8>) -> i32 {
+    visible_trees(grid).into_iter().filter(|seen| *seen).count() as i32
+}
+
+pub(crate) const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+// Parsing is split from solving so both can be timed separately
+pub(crate) fn parse(input: &str) -> Grid<u8> {
+    Grid::from_str_with(input, |c| c as u8 - b'0')
+}
+
+pub(crate) fn solve_parsed(grid: &Grid<u8>) -> DayOutput {
+    let seen_tree_count = count_trees(grid);
+    let treehouse_score = find_treehouse_spot(grid);
+
+    DayOutput::two(
+        Some(PartResult::Int(seen_tree_count)),
+        Some(PartResult::Int(treehouse_score)),
+    )
 }
 
 // https://adventofcode.com/2022/day/8
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let grid = Grid::from_str(input);
+    Ok(solve_parsed(&parse(input)))
+}
+
+pub struct Day8;
 
-    let seen_tree_count = count_trees(&grid);
-    let treehouse_score = find_treehouse_spot(&grid);
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(seen_tree_count)),
-        part2: Some(PartResult::Int(treehouse_score)),
-    })
+    fn example() -> &'static str {
+        EXAMPLE
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grid::{iterators::GridIterator, Direction};
+
+    // Brute force reference for treehouse_scores
+    fn score_treehouse_spot(grid: &Grid<u8>, position: Vec2D<usize>) -> i32 {
+        let top_sightline_count = count_visible_trees(grid, position, Direction::Up);
+        let bottom_sightline_count = count_visible_trees(grid, position, Direction::Down);
+        let right_sightline_count = count_visible_trees(grid, position, Direction::Right);
+        let left_sightline_count = count_visible_trees(grid, position, Direction::Left);
+
+        top_sightline_count * right_sightline_count * bottom_sightline_count * left_sightline_count
+    }
+
+    fn count_visible_trees(grid: &Grid<u8>, position: Vec2D<usize>, dir: Direction) -> i32 {
+        let mut a = grid.line_iter(position, dir);
+
+        let max_tree_size = *a.next().expect("First tree to be valid").1; // Skip the starting tile and use it as height cap
+
+        let mut count = 0;
+
+        for entry in a {
+            count += 1;
+            let tree_height = *entry.1;
+
+            if tree_height >= max_tree_size {
+                break;
+            }
+        }
+
+        count
+    }
 
     #[test]
     fn day() -> Result<(), String> {
@@ -152,15 +206,7 @@ mod tests {
 
     #[test]
     fn grid_edge_iter() -> Result<(), String> {
-        #[rustfmt::skip]
-        let input = [
-            "30373", 
-            "25512", 
-            "65332", 
-            "33549", 
-            "35390"].join("\n");
-
-        let grid = Grid::from_str(&input);
+        let grid = Grid::from_str(EXAMPLE);
         let mut iter = grid.edges();
 
         // First vertical
@@ -231,65 +277,92 @@ mod tests {
 
     #[test]
     fn tree_count() {
-        #[rustfmt::skip]
-        let input = [
-            "30373", 
-            "25512", 
-            "65332", 
-            "33549", 
-            "35390"].join("\n");
-
-        let grid = Grid::from_str(&input);
+        let grid = Grid::from_str(EXAMPLE).to_digits();
 
         assert_eq!(count_trees(&grid), 21);
+
+        // Every edge tree is visible, along with these interior ones
+        let interior: Vec<Vec2D<i32>> = visible_trees(&grid)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, seen)| *seen)
+            .filter_map(|(index, _)| grid.position_of_index(index))
+            .filter(|pos| pos.x > 0 && pos.x < 4 && pos.y > 0 && pos.y < 4)
+            .collect();
+        assert_eq!(
+            interior,
+            vec![
+                Vec2D { x: 1, y: 1 },
+                Vec2D { x: 2, y: 1 },
+                Vec2D { x: 1, y: 2 },
+                Vec2D { x: 3, y: 2 },
+                Vec2D { x: 2, y: 3 }
+            ]
+        );
     }
 
     #[test]
     fn treehouse_score_single_a() {
-        #[rustfmt::skip]
-        let input = [
-            "30373", 
-            "25512", 
-            "65332", 
-            "33549", 
-            "35390"].join("\n");
-
-        let grid = Grid::from_str(&input);
+        let grid = Grid::from_str(EXAMPLE).to_digits();
 
+        assert_eq!(treehouse_scores(&grid)[3 * grid.width() + 2], 8);
         assert_eq!(score_treehouse_spot(&grid, Vec2D { x: 2, y: 3 }), 8);
     }
 
     #[test]
     fn treehouse_score_single_b() {
-        #[rustfmt::skip]
-        let input = [
-            "30373", 
-            "25512", 
-            "65332", 
-            "33549", 
-            "35390"].join("\n");
-
-        let grid = Grid::from_str(&input);
+        let grid = Grid::from_str(EXAMPLE).to_digits();
 
+        assert_eq!(treehouse_scores(&grid)[grid.width() + 2], 4);
         assert_eq!(score_treehouse_spot(&grid, Vec2D { x: 2, y: 1 }), 4);
     }
 
+    #[test]
+    fn treehouse_scores_match_brute_force_on_example() {
+        let grid = Grid::from_str(EXAMPLE).to_digits();
+
+        let brute_force: Vec<i32> = grid
+            .iter_with_pos()
+            .map(|(pos, _)| score_treehouse_spot(&grid, pos))
+            .collect();
+
+        assert_eq!(treehouse_scores(&grid), brute_force);
+    }
+
     #[test]
     fn treehouse_find() {
-        #[rustfmt::skip]
-        let input = [
-            "30373", 
-            "25512", 
-            "65332", 
-            "33549", 
-            "35390"].join("\n");
-
-        let grid = Grid::from_str(&input);
+        let grid = Grid::from_str(EXAMPLE).to_digits();
         let score = find_treehouse_spot(&grid);
 
         assert_eq!(score, 8);
     }
 
+    #[test]
+    fn treehouse_scores_match_brute_force() {
+        // Simple LCG, good enough for a test grid
+        let mut seed: u32 = 12345;
+        let mut next_height = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            b'0' + ((seed >> 16) % 10) as u8
+        };
+
+        let input = (0..13)
+            .map(|_| (0..13).map(|_| next_height() as char).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let grid = Grid::from_str(&input).to_digits();
+        let scores = treehouse_scores(&grid);
+
+        for (position, _) in grid.iter_with_pos() {
+            assert_eq!(
+                scores[position.x + position.y * grid.width()],
+                score_treehouse_spot(&grid, position),
+                "Score mismatch at {position:?}"
+            );
+        }
+    }
+
     #[test]
     fn grid_iter() {
         let mut iter = GridIterator::new(2, 2);
@@ -299,4 +372,24 @@ mod tests {
         assert_eq!(iter.next().unwrap(), Vec2D { x: 0, y: 1 });
         assert_eq!(iter.next().unwrap(), Vec2D { x: 1, y: 1 });
     }
+
+    #[test]
+    fn parse_example() {
+        let grid = parse(EXAMPLE);
+
+        assert_eq!(grid.width(), 5);
+        assert_eq!(grid.height(), 5);
+        assert_eq!(grid.get(0, 0), Some(&3));
+        assert_eq!(grid.get(2, 2), Some(&3));
+        assert_eq!(grid.get(4, 3), Some(&9));
+    }
+
+    #[test]
+    fn solve_matches_parse_then_solve_parsed() {
+        let parsed = parse(EXAMPLE);
+        super::super::tests::assert_day_output_eq(
+            &super::solve(EXAMPLE).ok().unwrap(),
+            &solve_parsed(&parsed),
+        );
+    }
 }
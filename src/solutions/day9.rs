@@ -1,8 +1,10 @@
 use std::{collections::HashSet, str::FromStr};
 
-use crate::vec2d::Vec2D;
+use crate::vec2d::{Vec2D, Vec2DBounds};
 
-use super::{DayOutput, LogicError, PartResult};
+use super::{DayOutput, LogicError, PartResult, Solution};
+
+const VISUALIZE_LONG_ROPE: bool = false;
 
 enum Direction {
     Up,
@@ -56,21 +58,8 @@ impl RopeSnake for [Vec2D<i32>] {
     }
 }
 
-fn sign(x: i32) -> i32 {
-    match x.cmp(&0) {
-        std::cmp::Ordering::Less => -1,
-        std::cmp::Ordering::Equal => 0,
-        std::cmp::Ordering::Greater => 1,
-    }
-}
-
 fn update_tail_pos(tail: &mut Vec2D<i32>, head: &Vec2D<i32>) {
-    let mut vec_to_head = *head - *tail;
-
-    vec_to_head.x = sign(vec_to_head.x);
-    vec_to_head.y = sign(vec_to_head.y);
-
-    *tail = *tail + vec_to_head;
+    *tail = tail.step_towards(head);
 }
 
 impl FromStr for Movement {
@@ -93,37 +82,141 @@ impl FromStr for Movement {
     }
 }
 
+pub(crate) const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
 // https://adventofcode.com/2022/day/9
 pub fn solve(input: &str) -> Result<DayOutput, LogicError> {
-    let mut short_rope = [Vec2D::default(); 2];
-    let mut short_rope_seen_positions: HashSet<Vec2D<i32>> = HashSet::new();
-
-    let mut long_rope = [Vec2D::default(); 10];
-    let mut long_rope_seen_positions: HashSet<Vec2D<i32>> = HashSet::new();
+    // The second knot only follows the head, so it moves exactly like the tail of a 2 knot rope
+    let mut rope = [Vec2D::default(); 10];
+    let mut second_knot_seen_positions: HashSet<Vec2D<i32>> = HashSet::new();
+    let mut tail_seen_positions: HashSet<Vec2D<i32>> = HashSet::new();
 
     input
         .lines()
         .map(|line| line.parse::<Movement>().unwrap())
         .for_each(|movement| {
             for _ in 0..movement.distance {
-                short_rope.move_head(&movement.direction);
-                short_rope_seen_positions.insert(short_rope.get_tail());
+                rope.move_head(&movement.direction);
+                second_knot_seen_positions.insert(rope[1]);
+                tail_seen_positions.insert(rope.get_tail());
+            }
 
-                long_rope.move_head(&movement.direction);
-                long_rope_seen_positions.insert(long_rope.get_tail());
+            if VISUALIZE_LONG_ROPE {
+                print_rope(&rope, &tail_seen_positions);
             }
         });
 
-    Ok(DayOutput {
-        part1: Some(PartResult::Int(short_rope_seen_positions.len() as i32)),
-        part2: Some(PartResult::Int(long_rope_seen_positions.len() as i32)),
-    })
+    Ok(DayOutput::two(
+        Some(PartResult::Int(second_knot_seen_positions.len() as i32)),
+        Some(PartResult::Int(tail_seen_positions.len() as i32)),
+    ))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+
+    fn solve(input: &str) -> Result<DayOutput, LogicError> {
+        solve(input)
+    }
+
+    fn example() -> &'static str {
+        EXAMPLE
+    }
+}
+
+fn print_rope(knots: &[Vec2D<i32>], visited: &HashSet<Vec2D<i32>>) {
+    let bounds = knots
+        .iter()
+        .chain(visited.iter())
+        .copied()
+        .chain([Vec2D::default()])
+        .bounds_iter();
+
+    println!("{}", render_rope(knots, visited, bounds));
+}
+
+// Draws the head as H and the other knots by their index, earlier knots cover later ones. Visited cells are drawn as #
+fn render_rope(
+    knots: &[Vec2D<i32>],
+    visited: &HashSet<Vec2D<i32>>,
+    (min, max): (Vec2D<i32>, Vec2D<i32>),
+) -> String {
+    let mut out = String::new();
+
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let pos = Vec2D { x, y };
+            let c = match knots.iter().position(|knot| *knot == pos) {
+                Some(0) => 'H',
+                Some(n) => char::from_digit(n as u32, 10).unwrap_or('T'),
+                None if visited.contains(&pos) => '#',
+                None => '.',
+            };
+            out.push(c);
+        }
+        out.push('\n');
+    }
+
+    out
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use crate::vec2d::Vec2D;
+
+    use super::{render_rope, Movement, RopeSnake, EXAMPLE};
+
     #[test]
     fn day() -> Result<(), String> {
         super::super::tests::test_day(9, super::solve)
     }
+
+    #[test]
+    fn render() {
+        let knots = [
+            Vec2D { x: 2, y: 0 },
+            Vec2D { x: 1, y: 0 },
+            Vec2D { x: 1, y: 0 },
+            Vec2D { x: 0, y: 1 },
+        ];
+        let visited = HashSet::from([Vec2D { x: 0, y: 1 }, Vec2D { x: 2, y: 1 }]);
+
+        let rendering = render_rope(
+            &knots,
+            &visited,
+            (Vec2D { x: -1, y: 0 }, Vec2D { x: 2, y: 1 }),
+        );
+
+        assert_eq!(rendering, "..1H\n.3.#\n");
+    }
+
+    #[test]
+    fn short_rope_matches_second_knot() {
+        let mut short_rope = [Vec2D::default(); 2];
+        let mut long_rope = [Vec2D::default(); 10];
+
+        for movement in EXAMPLE
+            .lines()
+            .map(|line| line.parse::<Movement>().unwrap())
+        {
+            for _ in 0..movement.distance {
+                short_rope.move_head(&movement.direction);
+                long_rope.move_head(&movement.direction);
+
+                assert_eq!(short_rope.get_tail(), long_rope[1]);
+            }
+        }
+    }
 }
@@ -0,0 +1,66 @@
+/// Applies step to the state for as long as it returns true
+/// That is usually "the state changed", but may be any condition to keep going, like a simulation not being done yet
+pub fn fixpoint<S>(initial: S, mut step: impl FnMut(&mut S) -> bool) -> S {
+    let mut state = initial;
+    while step(&mut state) {}
+    state
+}
+
+/// Like `fixpoint`, but gives up with an error if the state hasn't settled after max_iterations steps
+pub fn fixpoint_capped<S>(
+    initial: S,
+    max_iterations: usize,
+    mut step: impl FnMut(&mut S) -> bool,
+) -> Result<S, String> {
+    let mut state = initial;
+    for _ in 0..max_iterations {
+        if !step(&mut state) {
+            return Ok(state);
+        }
+    }
+    Err(format!(
+        "State did not settle within {max_iterations} iterations"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_until_threshold() {
+        let mut steps = 0;
+        let counter = fixpoint(0, |n| {
+            steps += 1;
+            if *n < 10 {
+                *n += 1;
+                true
+            } else {
+                false
+            }
+        });
+
+        assert_eq!(counter, 10);
+        assert_eq!(steps, 11); // The final step sees no change
+    }
+
+    #[test]
+    fn capped() {
+        let step = |n: &mut i32| {
+            if *n < 10 {
+                *n += 1;
+                true
+            } else {
+                false
+            }
+        };
+
+        assert_eq!(fixpoint_capped(0, 11, step), Ok(10));
+        assert!(fixpoint_capped(0, 10, step).is_err());
+        assert!(fixpoint_capped(0, 1000, |n: &mut i32| {
+            *n += 1;
+            true
+        })
+        .is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     ops::{Add, Sub},
     str::FromStr,
 };
@@ -15,19 +15,63 @@ pub const DOWN: Vec2D<i32> = Vec2D { x: 0, y: 1 };
 pub const LEFT: Vec2D<i32> = Vec2D { x: -1, y: 0 };
 pub const RIGHT: Vec2D<i32> = Vec2D { x: 1, y: 0 };
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseVec2DError {
+    NoSeparator(String),
+    TooManyCommas(String),
+    InvalidNumber(String),
+}
+
+impl Display for ParseVec2DError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSeparator(s) => write!(f, "Expected a comma or whitespace in \"{s}\""),
+            Self::TooManyCommas(s) => write!(f, "Expected a single comma in \"{s}\""),
+            Self::InvalidNumber(s) => write!(f, "Could not parse number \"{s}\""),
+        }
+    }
+}
+
+impl From<ParseVec2DError> for String {
+    fn from(value: ParseVec2DError) -> Self {
+        value.to_string()
+    }
+}
+
 impl<T> FromStr for Vec2D<T>
 where
     T: FromStr,
 {
-    type Err = String;
+    type Err = ParseVec2DError;
 
+    /// Accepts "x,y", "x y" and either of them wrapped in parentheses, like "(x, y)"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (left, right) = s.split_once(',').ok_or("Could not split string")?;
-        let a: Self = Self {
-            x: left.parse().map_err(|_| "Could not parse left")?,
-            y: right.parse().map_err(|_| "Could not parse right")?,
+        let trimmed = s.trim();
+        let inner = trimmed
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+            .unwrap_or(trimmed);
+
+        let (left, right) = match inner.split_once(',') {
+            Some((_, right)) if right.contains(',') => {
+                return Err(ParseVec2DError::TooManyCommas(s.to_owned()))
+            }
+            Some(split) => split,
+            None => inner
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| ParseVec2DError::NoSeparator(s.to_owned()))?,
+        };
+
+        let parse = |n: &str| {
+            n.parse()
+                .map_err(|_| ParseVec2DError::InvalidNumber(n.to_owned()))
         };
-        Ok(a)
+
+        Ok(Self {
+            x: parse(left.trim())?,
+            y: parse(right.trim())?,
+        })
     }
 }
 
@@ -99,6 +143,56 @@ impl Vec2D<i32> {
             y: self.y * factor,
         }
     }
+
+    /// Moves one unit towards the target on each axis
+    pub fn step_towards(&self, target: &Self) -> Self {
+        *self + (*target - *self).normalized()
+    }
+
+    /// Iterates from self to target by repeatedly stepping towards it, both ends included
+    pub fn points_between(&self, target: &Self) -> impl Iterator<Item = Self> {
+        let target = *target;
+        let mut next = Some(*self);
+
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = (current != target).then(|| current.step_towards(&target));
+            Some(current)
+        })
+    }
+
+    /// Iterates over every point at exactly the given manhattan distance, going around counterclockwise from the right
+    pub fn manhattan_ring(&self, radius: i32) -> impl Iterator<Item = Self> {
+        let center = *self;
+
+        // A radius of 0 is just the center itself
+        let center_only = (radius == 0).then_some(center);
+
+        // Each side of the diamond contributes one point per step, excluding the corner it ends on
+        let sides = (0..radius).flat_map(move |i| {
+            [
+                Self {
+                    x: radius - i,
+                    y: i,
+                },
+                Self {
+                    x: -i,
+                    y: radius - i,
+                },
+                Self {
+                    x: -radius + i,
+                    y: -i,
+                },
+                Self {
+                    x: i,
+                    y: -radius + i,
+                },
+            ]
+            .map(|offset| center + offset)
+        });
+
+        center_only.into_iter().chain(sides)
+    }
 }
 
 trait Bounds {
@@ -176,6 +270,7 @@ impl<I> Vec2DBounds for I where I: Iterator<Item = Vec2D<i32>> {}
 mod tests {
     use std::vec;
 
+    use super::ParseVec2DError;
     use super::Vec2D;
     use super::Vec2DBounds;
 
@@ -195,4 +290,144 @@ mod tests {
         assert_eq!(max.x, 62);
         assert_eq!(max.y, 55);
     }
+
+    #[test]
+    fn step_towards() {
+        let origin = Vec2D { x: 0, y: 0 };
+
+        assert_eq!(
+            origin.step_towards(&Vec2D { x: 5, y: -3 }),
+            Vec2D { x: 1, y: -1 }
+        );
+        assert_eq!(
+            origin.step_towards(&Vec2D { x: 0, y: 4 }),
+            Vec2D { x: 0, y: 1 }
+        );
+        assert_eq!(origin.step_towards(&origin), origin);
+    }
+
+    #[test]
+    fn points_between_horizontal() {
+        let points: Vec<Vec2D<i32>> = Vec2D { x: 3, y: 1 }
+            .points_between(&Vec2D { x: 0, y: 1 })
+            .collect();
+
+        assert_eq!(
+            points,
+            vec![
+                Vec2D { x: 3, y: 1 },
+                Vec2D { x: 2, y: 1 },
+                Vec2D { x: 1, y: 1 },
+                Vec2D { x: 0, y: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn points_between_vertical() {
+        let points: Vec<Vec2D<i32>> = Vec2D { x: 2, y: 4 }
+            .points_between(&Vec2D { x: 2, y: 6 })
+            .collect();
+
+        assert_eq!(
+            points,
+            vec![
+                Vec2D { x: 2, y: 4 },
+                Vec2D { x: 2, y: 5 },
+                Vec2D { x: 2, y: 6 }
+            ]
+        );
+    }
+
+    #[test]
+    fn points_between_diagonal() {
+        let points: Vec<Vec2D<i32>> = Vec2D { x: 0, y: 0 }
+            .points_between(&Vec2D { x: -2, y: 2 })
+            .collect();
+
+        assert_eq!(
+            points,
+            vec![
+                Vec2D { x: 0, y: 0 },
+                Vec2D { x: -1, y: 1 },
+                Vec2D { x: -2, y: 2 }
+            ]
+        );
+    }
+
+    fn ring_of(radius: i32) -> Vec<Vec2D<i32>> {
+        let center = Vec2D { x: 3, y: -2 };
+        let ring: Vec<Vec2D<i32>> = center.manhattan_ring(radius).collect();
+
+        assert!(ring.iter().all(|p| p.distance_manhatten(&center) == radius));
+        assert_eq!(
+            ring.iter().collect::<std::collections::HashSet<_>>().len(),
+            ring.len(),
+            "Ring should not contain duplicates"
+        );
+
+        ring
+    }
+
+    #[test]
+    fn manhattan_ring() {
+        assert_eq!(ring_of(0), vec![Vec2D { x: 3, y: -2 }]);
+        assert_eq!(ring_of(1).len(), 4);
+        assert_eq!(ring_of(2).len(), 8);
+        assert_eq!(ring_of(5).len(), 20);
+    }
+
+    #[test]
+    fn points_between_single() {
+        let point = Vec2D { x: 7, y: 7 };
+
+        assert_eq!(
+            point.points_between(&point).collect::<Vec<_>>(),
+            vec![point]
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("498,-4".parse(), Ok(Vec2D { x: 498, y: -4 }));
+    }
+
+    #[test]
+    fn parse_formats() {
+        let expected = Ok(Vec2D { x: 3, y: 4 });
+
+        assert_eq!("3,4".parse(), expected);
+        assert_eq!("3 4".parse(), expected);
+        assert_eq!("(3, 4)".parse(), expected);
+        assert_eq!("(3,4)".parse(), expected);
+        assert_eq!(" ( 3  4 ) ".parse(), expected);
+
+        assert_eq!(
+            "3 4 5".parse::<Vec2D<i32>>(),
+            Err(ParseVec2DError::InvalidNumber("4 5".to_owned()))
+        );
+        assert_eq!(
+            "(3)".parse::<Vec2D<i32>>(),
+            Err(ParseVec2DError::NoSeparator("(3)".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "1,2,3".parse::<Vec2D<i32>>(),
+            Err(ParseVec2DError::TooManyCommas("1,2,3".to_owned()))
+        );
+        assert_eq!(
+            "abc,2".parse::<Vec2D<i32>>(),
+            Err(ParseVec2DError::InvalidNumber("abc".to_owned()))
+        );
+        assert_eq!(
+            "12".parse::<Vec2D<i32>>(),
+            Err(ParseVec2DError::NoSeparator("12".to_owned()))
+        );
+
+        let message: String = "abc,2".parse::<Vec2D<i32>>().unwrap_err().into();
+        assert!(message.contains("\"abc\""), "{message}");
+    }
 }